@@ -26,6 +26,8 @@ pub fn derive_contract_function_set<'a>(
                 }
             }).multiunzip();
             let num_args = args.len();
+            // Return values go through `try_into_val`, so methods returning a
+            // tuple `(A, B, ...)` hand their caller a host vec of the elements.
             let func_call = quote! {
                 #i => {
                     if args.len() == #num_args {
@@ -7,6 +7,8 @@ mod wasmi_helper;
 pub(crate) use limits::DepthLimiter;
 pub use limits::{DEFAULT_HOST_DEPTH_LIMIT, DEFAULT_XDR_RW_LIMITS};
 pub use model::{MeteredCostComponent, ScaledU64};
+#[cfg(any(test, feature = "testutils"))]
+pub use util::BudgetDelta;
 pub(crate) use wasmi_helper::{get_wasmi_config, load_calibrated_fuel_costs};
 
 use std::{
@@ -1197,6 +1199,29 @@ impl Budget {
         Ok(())
     }
 
+    /// Runs `f` with the cpu and memory limits lowered, where necessary, so
+    /// that at most `cpu` more instructions and `mem` more bytes can be
+    /// charged, then restores the limits. Whatever `f` consumes stays
+    /// charged, and the limits are restored even if `f` fails.
+    pub(crate) fn with_capped_limits<T, F>(&self, cpu: u64, mem: u64, f: F) -> Result<T, HostError>
+    where
+        F: FnOnce() -> Result<T, HostError>,
+    {
+        let (cpu_limit, mem_limit) = self.with_mut_budget(|mut b| {
+            let limits = (b.cpu_insns.limit, b.mem_bytes.limit);
+            b.cpu_insns.limit = limits.0.min(b.cpu_insns.total_count.saturating_add(cpu));
+            b.mem_bytes.limit = limits.1.min(b.mem_bytes.total_count.saturating_add(mem));
+            Ok(limits)
+        })?;
+        let res = f();
+        self.with_mut_budget(|mut b| {
+            b.cpu_insns.limit = cpu_limit;
+            b.mem_bytes.limit = mem_limit;
+            Ok(())
+        })?;
+        res
+    }
+
     pub(crate) fn ensure_shadow_cpu_limit_factor(&self, factor: u64) -> Result<(), HostError> {
         let mut b = self.0.try_borrow_mut_or_err()?;
         b.cpu_insns.shadow_limit = b.cpu_insns.limit.saturating_mul(factor);
@@ -1219,6 +1244,26 @@ impl Budget {
             .ok_or_else(|| (ScErrorType::Budget, ScErrorCode::InternalError).into())
     }
 
+    /// Returns the cpu and memory consumed by each cost type whose cpu
+    /// consumption exceeds `cpu_threshold`, most expensive first. This is a
+    /// condensed form of the full budget report, for logging only the cost
+    /// types that matter.
+    pub fn report_above(
+        &self,
+        cpu_threshold: u64,
+    ) -> Result<Vec<(ContractCostType, u64, u64)>, HostError> {
+        let b = self.0.try_borrow_or_err()?;
+        let mut report: Vec<_> = ContractCostType::variants()
+            .into_iter()
+            .filter_map(|ty| {
+                let t = b.tracker.cost_trackers.get(ty as usize)?;
+                (t.cpu > cpu_threshold).then_some((ty, t.cpu, t.mem))
+            })
+            .collect();
+        report.sort_by(|a, b| b.1.cmp(&a.1));
+        Ok(report)
+    }
+
     pub fn get_time(&self, ty: ContractCostType) -> Result<u64, HostError> {
         self.0.try_borrow_or_err()?.tracker.get_time(ty)
     }
@@ -10,7 +10,10 @@ use crate::{budget::Budget, HostError};
 use crate::host::error::TryBorrowOrErr;
 
 #[cfg(any(test, feature = "testutils"))]
-use crate::{budget::model::ScaledU64, xdr::ContractCostType};
+use crate::{
+    budget::{model::ScaledU64, CostTracker},
+    xdr::ContractCostType,
+};
 
 #[cfg(any(test, feature = "testutils", feature = "bench"))]
 impl Budget {
@@ -127,8 +130,94 @@ impl Budget {
     }
 }
 
+/// The budget consumed while running some piece of code, in total and broken
+/// down by cost type. Returned by [`Budget::measure`].
+#[cfg(any(test, feature = "testutils"))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BudgetDelta {
+    pub cpu_insns: u64,
+    pub mem_bytes: u64,
+    cost_trackers: Vec<CostTracker>,
+}
+
+#[cfg(any(test, feature = "testutils"))]
+impl BudgetDelta {
+    /// Returns the consumption charged to cost type `ty`.
+    pub fn get_tracker(&self, ty: ContractCostType) -> CostTracker {
+        self.cost_trackers
+            .get(ty as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
 #[cfg(any(test, feature = "testutils"))]
 impl Budget {
+    /// Runs `f` and returns its result along with the budget it consumed.
+    /// Unlike a reset, this leaves the budget's running totals untouched, so
+    /// it can wrap any span of work in the middle of a test.
+    pub fn measure<T, F>(&self, f: F) -> Result<(T, BudgetDelta), HostError>
+    where
+        F: FnOnce() -> T,
+    {
+        let (cpu_before, mem_before, trackers_before) = {
+            let b = self.0.try_borrow_or_err()?;
+            (
+                b.cpu_insns.get_total_count(),
+                b.mem_bytes.get_total_count(),
+                b.tracker.cost_trackers,
+            )
+        };
+        let res = f();
+        let b = self.0.try_borrow_or_err()?;
+        let cost_trackers = b
+            .tracker
+            .cost_trackers
+            .iter()
+            .zip(trackers_before.iter())
+            .map(|(after, before)| CostTracker {
+                iterations: after.iterations.saturating_sub(before.iterations),
+                inputs: after
+                    .inputs
+                    .map(|i| i.saturating_sub(before.inputs.unwrap_or(0))),
+                cpu: after.cpu.saturating_sub(before.cpu),
+                mem: after.mem.saturating_sub(before.mem),
+            })
+            .collect();
+        let delta = BudgetDelta {
+            cpu_insns: b.cpu_insns.get_total_count().saturating_sub(cpu_before),
+            mem_bytes: b.mem_bytes.get_total_count().saturating_sub(mem_before),
+            cost_trackers,
+        };
+        Ok((res, delta))
+    }
+
+    /// Compares the consumption recorded by this budget with `other`'s, cost
+    /// type by cost type, and returns the cpu and memory differences (this
+    /// budget's minus `other`'s) for each cost type where either differs.
+    /// Running two variants of some code on two hosts and diffing their
+    /// budgets shows where one is cheaper than the other.
+    pub fn diff(&self, other: &Budget) -> Result<Vec<(ContractCostType, i64, i64)>, HostError> {
+        let ours = self.0.try_borrow_or_err()?.tracker.cost_trackers;
+        let theirs = other.0.try_borrow_or_err()?.tracker.cost_trackers;
+        let signed_sub = |a: u64, b: u64| -> i64 {
+            if a >= b {
+                i64::try_from(a - b).unwrap_or(i64::MAX)
+            } else {
+                i64::try_from(b - a).map_or(i64::MIN, |d| -d)
+            }
+        };
+        Ok(ContractCostType::variants()
+            .into_iter()
+            .filter_map(|ty| {
+                let (a, b) = (ours[ty as usize], theirs[ty as usize]);
+                let cpu = signed_sub(a.cpu, b.cpu);
+                let mem = signed_sub(a.mem, b.mem);
+                (cpu != 0 || mem != 0).then_some((ty, cpu, mem))
+            })
+            .collect())
+    }
+
     pub(crate) fn override_model_with_scaled_params(
         &self,
         ty: ContractCostType,
@@ -1,10 +1,13 @@
 use crate::{
     host::{metered_clone::MeteredClone, Host, HostError},
     xdr::{AccountId, ScAddress, ScErrorCode, ScErrorType},
-    AddressObject, BytesObject, Compare, Env, EnvBase, StringObject, TryFromVal, U32Val, Val,
-    VecObject,
+    AddressObject, BytesObject, Compare, Env, EnvBase, I256Val, StringObject, TryFromVal, U32Val,
+    Val, VecObject,
+};
+use core::{
+    cmp::Ordering,
+    ops::{Add, Div, Mul, Sub},
 };
-use core::cmp::Ordering;
 
 #[derive(Clone)]
 pub(crate) struct String {
@@ -380,3 +383,151 @@ impl Address {
         self.object
     }
 }
+
+/// A signed 256-bit integer living in the host, exported as `HostI256`.
+/// Arithmetic is delegated to the `i256_*` host functions; since any of them
+/// can fail (on overflow or division by zero) the operator impls produce a
+/// `Result` rather than panicking, so native code can write `(&a + &b)?`.
+///
+/// Comparison goes through the host too, and so can fail on budget
+/// exhaustion. The `PartialOrd` impl reports such a failure as an unordered
+/// pair (and `PartialEq` as unequal); callers that need the error should use
+/// [`I256::compare`].
+#[derive(Clone)]
+pub struct I256 {
+    host: Host,
+    val: I256Val,
+}
+
+impl Compare<I256> for Host {
+    type Error = HostError;
+
+    fn compare(&self, a: &I256, b: &I256) -> Result<Ordering, Self::Error> {
+        self.compare(&a.val.to_val(), &b.val.to_val())
+    }
+}
+
+impl PartialEq for I256 {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(self.compare(other), Ok(Ordering::Equal))
+    }
+}
+
+impl PartialOrd for I256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.compare(other).ok()
+    }
+}
+
+impl TryFromVal<Host, I256Val> for I256 {
+    type Error = HostError;
+
+    fn try_from_val(env: &Host, val: &I256Val) -> Result<Self, Self::Error> {
+        Ok(I256 {
+            host: env.clone(),
+            val: *val,
+        })
+    }
+}
+
+impl TryFromVal<Host, Val> for I256 {
+    type Error = HostError;
+
+    fn try_from_val(env: &Host, val: &Val) -> Result<Self, Self::Error> {
+        let val = *val;
+        let i256: I256Val = val.try_into()?;
+        I256::try_from_val(env, &i256)
+    }
+}
+
+impl TryFromVal<Host, crate::I256> for I256 {
+    type Error = HostError;
+
+    fn try_from_val(env: &Host, num: &crate::I256) -> Result<Self, Self::Error> {
+        let val = I256Val::try_from_val(env, num)?;
+        I256::try_from_val(env, &val)
+    }
+}
+
+impl TryFromVal<Host, I256> for Val {
+    type Error = HostError;
+
+    fn try_from_val(_env: &Host, val: &I256) -> Result<Val, Self::Error> {
+        Ok(val.val.to_val())
+    }
+}
+
+impl From<I256> for I256Val {
+    fn from(i: I256) -> Self {
+        i.val
+    }
+}
+
+impl Add for &I256 {
+    type Output = Result<I256, HostError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs)
+    }
+}
+
+impl Sub for &I256 {
+    type Output = Result<I256, HostError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+    }
+}
+
+impl Mul for &I256 {
+    type Output = Result<I256, HostError>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs)
+    }
+}
+
+impl Div for &I256 {
+    type Output = Result<I256, HostError>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.checked_div(rhs)
+    }
+}
+
+impl I256 {
+    fn wrap(&self, val: I256Val) -> I256 {
+        I256 {
+            host: self.host.clone(),
+            val,
+        }
+    }
+
+    pub fn checked_add(&self, other: &I256) -> Result<I256, HostError> {
+        Ok(self.wrap(self.host.i256_add(self.val, other.val)?))
+    }
+
+    pub fn checked_sub(&self, other: &I256) -> Result<I256, HostError> {
+        Ok(self.wrap(self.host.i256_sub(self.val, other.val)?))
+    }
+
+    pub fn checked_mul(&self, other: &I256) -> Result<I256, HostError> {
+        Ok(self.wrap(self.host.i256_mul(self.val, other.val)?))
+    }
+
+    pub fn checked_div(&self, other: &I256) -> Result<I256, HostError> {
+        Ok(self.wrap(self.host.i256_div(self.val, other.val)?))
+    }
+
+    pub fn compare(&self, other: &I256) -> Result<Ordering, HostError> {
+        self.host.compare(self, other)
+    }
+
+    pub fn to_i256(&self) -> Result<crate::I256, HostError> {
+        Ok(crate::I256::try_from_val(&self.host, &self.val.to_val())?)
+    }
+
+    pub fn as_val(&self) -> I256Val {
+        self.val
+    }
+}
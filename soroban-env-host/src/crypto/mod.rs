@@ -3,8 +3,9 @@ use crate::host::prng::SEED_BYTES;
 use crate::{
     budget::AsBudget,
     err,
+    host_object::HostVec,
     xdr::{ContractCostType, Hash, ScBytes, ScErrorCode, ScErrorType},
-    BytesObject, Error, Host, HostError, U32Val, Val,
+    BytesObject, Error, Host, HostError, TryFromVal, U32Val, Val, VecObject,
 };
 use elliptic_curve::scalar::IsHigh;
 use hex_literal::hex;
@@ -61,6 +62,22 @@ impl Host {
         })
     }
 
+    /// Like [`Host::ed25519_pub_key_from_bytesobj_input`], but reports whether
+    /// `k` would be accepted as a public key instead of failing on a key of
+    /// the wrong length or one that is not a point on the curve.
+    pub(crate) fn ed25519_pub_key_is_valid_bytesobj_input(
+        &self,
+        k: BytesObject,
+    ) -> Result<bool, HostError> {
+        self.visit_obj(k, |bytes: &ScBytes| {
+            self.charge_budget(ContractCostType::ComputeEd25519PubKey, None)?;
+            Ok(bytes
+                .as_slice()
+                .try_into()
+                .is_ok_and(|vk_bytes| ed25519_dalek::VerifyingKey::from_bytes(vk_bytes).is_ok()))
+        })
+    }
+
     pub(crate) fn verify_sig_ed25519_internal(
         &self,
         payload: &[u8],
@@ -302,6 +319,45 @@ impl Host {
             Ok(hash)
         })
     }
+
+    /// Computes the SHA-256 Merkle root of a vector of 32-byte leaves. Each
+    /// level is reduced by hashing the concatenation of adjacent pairs of
+    /// nodes, and a level with an odd number of nodes pairs its last node
+    /// with itself. A single leaf is its own root; an empty vector has no
+    /// well-defined root and is an error.
+    pub(crate) fn merkle_root_sha256_from_vecobj_input(
+        &self,
+        leaves: VecObject,
+    ) -> Result<Hash, HostError> {
+        let mut level = self.visit_obj(leaves, |hv: &HostVec| {
+            let mut level = Vec::<Hash>::with_metered_capacity(hv.len(), self)?;
+            for leaf in hv.iter() {
+                let leaf = BytesObject::try_from_val(self, leaf)?;
+                level.push(self.hash_from_bytesobj_input("leaf", leaf)?);
+            }
+            Ok(level)
+        })?;
+        if level.is_empty() {
+            return Err(self.err(
+                ScErrorType::Crypto,
+                ScErrorCode::InvalidInput,
+                "merkle root of an empty set of leaves is undefined",
+                &[leaves.to_val()],
+            ));
+        }
+        let mut buf = [0u8; 64];
+        while level.len() > 1 {
+            let mut next = Vec::<Hash>::with_metered_capacity(level.len().div_ceil(2), self)?;
+            for pair in level.chunks(2) {
+                let right = pair.last().unwrap_or(&pair[0]);
+                buf[..32].copy_from_slice(&pair[0].0);
+                buf[32..].copy_from_slice(&right.0);
+                next.push(Hash(sha256_hash_from_bytes_raw(&buf, self)?));
+            }
+            level = next;
+        }
+        Ok(level.swap_remove(0))
+    }
 }
 
 pub(crate) fn sha256_hash_from_bytes_raw(
@@ -188,8 +188,22 @@ impl Host {
         }
     }
 
+    // Metering: covered by `to_vec`. Arguments that came from a VM have
+    // already passed `check_val_integrity`, but a native caller can construct
+    // any bit pattern, so each argument is checked to be a well-formed `Val`
+    // before it can reach contract code. This only inspects the bits of each
+    // `Val` and is not charged.
     pub(crate) fn call_args_from_obj(&self, args: VecObject) -> Result<Vec<Val>, HostError> {
-        self.visit_obj(args, |hv: &HostVec| hv.to_vec(self.as_budget()))
+        let args = self.visit_obj(args, |hv: &HostVec| hv.to_vec(self.as_budget()))?;
+        if args.iter().any(|arg| !arg.is_good()) {
+            return Err(self.err(
+                ScErrorType::Value,
+                ScErrorCode::InvalidInput,
+                "malformed Val in contract call arguments",
+                &[],
+            ));
+        }
+        Ok(args)
     }
 
     // Metering: covered by vals_to_vec
@@ -341,6 +355,50 @@ impl Host {
         })
     }
 
+    // Version of `to_host_val` that, when object interning is enabled (test
+    // builds only), returns the handle of the object previously built from an
+    // identical top-level value. Only the top-level value is hashed and looked
+    // up, so deep values cost one pass over their XDR.
+    pub(crate) fn to_host_val_interned(&self, v: &ScVal) -> Result<Val, HostError> {
+        #[cfg(any(test, feature = "testutils"))]
+        if self.try_borrow_interned_objects()?.is_some() {
+            let key = self.metered_hash_xdr(v)?;
+            self.charge_interned_objects_lookup()?;
+            let existing = self
+                .try_borrow_interned_objects()?
+                .as_ref()
+                .and_then(|cache| cache.get(&key).copied());
+            if let Some(obj) = existing {
+                return Ok(obj.into());
+            }
+            let val = self.to_host_val(v)?;
+            if let Ok(obj) = Object::try_from(val) {
+                // Each entry holds a key and a handle.
+                self.charge_budget(
+                    ContractCostType::MemAlloc,
+                    Some(core::mem::size_of::<([u8; 32], Object)>() as u64),
+                )?;
+                if let Some(cache) = self.try_borrow_interned_objects_mut()?.as_mut() {
+                    cache.insert(key, obj);
+                }
+            }
+            return Ok(val);
+        }
+        self.to_host_val(v)
+    }
+
+    // Charges for a lookup (or insertion) in the object-interning cache: one
+    // comparison of the 32-byte key per level of the tree.
+    #[cfg(any(test, feature = "testutils"))]
+    fn charge_interned_objects_lookup(&self) -> Result<(), HostError> {
+        let len = self
+            .try_borrow_interned_objects()?
+            .as_ref()
+            .map_or(0, |cache| cache.len());
+        let depth = (len as u64 + 1).ilog2() as u64 + 1;
+        self.charge_budget(ContractCostType::MemCmp, Some(32 * depth))
+    }
+
     // Version of `to_host_val` for the internal cases where the value has to
     // be valid by construction (e.g. read from ledger).
     pub(crate) fn to_valid_host_val(&self, v: &ScVal) -> Result<Val, HostError> {
@@ -1,21 +1,29 @@
-use core::cmp::min;
+use core::{
+    cmp::{min, Ordering},
+    mem::size_of,
+};
 use std::rc::Rc;
 
 use crate::{
     budget::AsBudget,
     err,
-    host::metered_clone::{MeteredAlloc, MeteredClone},
+    host::{
+        frame::StagedContractData,
+        metered_clone::{MeteredAlloc, MeteredClone},
+        metered_xdr::metered_write_xdr,
+    },
     storage::{InstanceStorageMap, Storage},
     vm::VersionedContractCodeCostInputs,
     xdr::{
-        AccountEntry, AccountId, Asset, BytesM, ContractCodeEntry, ContractDataDurability,
-        ContractDataEntry, ContractExecutable, ContractIdPreimage, ExtensionPoint, Hash,
-        HashIdPreimage, HashIdPreimageContractId, LedgerEntry, LedgerEntryData, LedgerEntryExt,
-        LedgerKey, LedgerKeyAccount, LedgerKeyContractCode, LedgerKeyContractData,
-        LedgerKeyTrustLine, PublicKey, ScAddress, ScContractInstance, ScErrorCode, ScErrorType,
-        ScMap, ScVal, Signer, SignerKey, ThresholdIndexes, TrustLineAsset, Uint256,
+        AccountEntry, AccountId, Asset, BytesM, ContractCodeEntry, ContractCostType,
+        ContractDataDurability, ContractDataEntry, ContractExecutable, ContractIdPreimage,
+        ExtensionPoint, Hash, HashIdPreimage, HashIdPreimageContractId, LedgerEntry,
+        LedgerEntryData, LedgerEntryExt, LedgerKey, LedgerKeyAccount, LedgerKeyContractCode,
+        LedgerKeyContractData, LedgerKeyTrustLine, PublicKey, ScAddress, ScContractInstance,
+        ScErrorCode, ScErrorType, ScMap, ScNonceKey, ScVal, ScVec, Signer, SignerKey,
+        ThresholdIndexes, TrustLineAsset, Uint256, VecM,
     },
-    AddressObject, Env, ErrorHandler, Host, HostError, StorageType, U32Val, Val,
+    AddressObject, Compare, Env, ErrorHandler, Host, HostError, StorageType, U32Val, Val,
 };
 
 impl Host {
@@ -164,6 +172,12 @@ impl Host {
             .has_with_host(&key, self, None)
     }
 
+    pub(crate) fn contract_instance_exists(&self, contract_id: &Hash) -> Result<bool, HostError> {
+        let key = self.contract_instance_ledger_key(contract_id)?;
+        self.try_borrow_storage_mut()?
+            .has_with_host(&key, self, None)
+    }
+
     // Stores the contract instance specified with its parts (executable and
     // storage).
     // When either of parts is `None`, the old value is preserved (when
@@ -295,6 +309,13 @@ impl Host {
         }))
     }
 
+    /// Returns the SHA-256 hash of the XDR encoding of `key`, hashed the same
+    /// way as a contract id preimage.
+    // metering: covered by components
+    pub fn hash_ledger_key(&self, key: &LedgerKey) -> Result<Hash, HostError> {
+        Ok(Hash(self.metered_hash_xdr(key)?))
+    }
+
     // notes on metering: `get` from storage is covered. Rest are free.
     pub(crate) fn load_account(&self, account_id: AccountId) -> Result<AccountEntry, HostError> {
         let acc = self.to_account_key(account_id)?;
@@ -463,11 +484,315 @@ impl Host {
         })
     }
 
+    /// Checks that `v` may be written under `k` to storage of type `t`
+    /// without writing it. Instance storage is always writable; for
+    /// ledger-backed storage the key is checked against the footprint and
+    /// the value against the limit set by [`Host::set_max_entry_size`].
+    // notes on metering: covered by components
+    pub(crate) fn check_contract_data_write(
+        &self,
+        k: Val,
+        v: Val,
+        t: StorageType,
+    ) -> Result<(), HostError> {
+        match t {
+            StorageType::Temporary | StorageType::Persistent => {
+                let key = self.storage_key_from_val(k, t.try_into()?)?;
+                self.try_borrow_storage_mut()?
+                    .check_write_access(&key, self.as_budget())?;
+                self.check_max_entry_size_of_val(k, v)
+            }
+            StorageType::Instance => Ok(()),
+        }
+    }
+
+    /// Writes `v` under `k` to storage of type `t`, as `put_contract_data`
+    /// does, except that the entry size has already been checked by
+    /// [`Host::check_contract_data_write`].
+    // notes on metering: covered by components
+    pub(crate) fn put_contract_data_typed(
+        &self,
+        k: Val,
+        v: Val,
+        t: StorageType,
+    ) -> Result<(), HostError> {
+        match t {
+            StorageType::Temporary | StorageType::Persistent => {
+                self.write_contract_data_into_ledger(k, v, t)
+            }
+            StorageType::Instance => self.with_mut_instance_storage(|s| {
+                s.map = s.map.insert(k, v, self)?;
+                Ok(())
+            }),
+        }
+    }
+
+    /// Reads the value under `k` from storage of type `t`, as
+    /// `get_contract_data` does, but returns `None` rather than an error if
+    /// there is no such entry.
+    // notes on metering: covered by components
+    pub(crate) fn try_get_contract_data_typed(
+        &self,
+        k: Val,
+        t: StorageType,
+    ) -> Result<Option<Val>, HostError> {
+        match t {
+            StorageType::Temporary | StorageType::Persistent => {
+                let key = self.storage_key_from_val(k, t.try_into()?)?;
+                let Some(entry) = self
+                    .try_borrow_storage_mut()?
+                    .try_get(&key, self, Some(k))?
+                else {
+                    return Ok(None);
+                };
+                match &entry.data {
+                    LedgerEntryData::ContractData(e) => Ok(Some(self.to_valid_host_val(&e.val)?)),
+                    _ => Err(self.err(
+                        ScErrorType::Storage,
+                        ScErrorCode::InternalError,
+                        "expected contract data ledger entry",
+                        &[],
+                    )),
+                }
+            }
+            StorageType::Instance => {
+                self.with_instance_storage(|s| Ok(s.map.get(&k, self)?.copied()))
+            }
+        }
+    }
+
+    /// Returns the ledger key under which the current contract keeps its
+    /// nonce for `address`. Nonces are kept in the contract's persistent
+    /// storage under `ScVal::LedgerKeyNonce` keys, which contracts can't
+    /// construct, so they can't collide with the contract's own data (or
+    /// with the temporary nonce entries consumed by authorization).
+    // notes on metering: covered by components
+    pub(crate) fn nonce_ledger_key(&self, address: &ScAddress) -> Result<Rc<LedgerKey>, HostError> {
+        let hash = self.metered_hash_xdr(address)?;
+        let mut nonce = [0u8; 8];
+        nonce.copy_from_slice(&hash[0..8]);
+        self.storage_key_for_address(
+            ScAddress::Contract(self.get_current_contract_id_internal()?),
+            ScVal::LedgerKeyNonce(ScNonceKey {
+                nonce: i64::from_be_bytes(nonce),
+            }),
+            ContractDataDurability::Persistent,
+        )
+    }
+
+    /// Returns the current contract's nonce for `address`, starting from 0,
+    /// and stores the incremented nonce under
+    /// [`Host::nonce_ledger_key`]. The entry holds `[address, nonce]`, so
+    /// that two addresses whose keys collide are reported as an error rather
+    /// than sharing a nonce.
+    // notes on metering: covered by components
+    pub(crate) fn get_and_incr_nonce_internal(
+        &self,
+        address: AddressObject,
+    ) -> Result<u64, HostError> {
+        let sc_address = self.scaddress_from_address(address)?;
+        let key = self.nonce_ledger_key(&sc_address)?;
+        let existing = self.try_borrow_storage_mut()?.try_get(&key, self, None)?;
+        let current = match existing {
+            None => 0,
+            Some(entry) => {
+                let stored = match &entry.data {
+                    LedgerEntryData::ContractData(e) => match &e.val {
+                        ScVal::Vec(Some(v)) => match v.as_slice() {
+                            [ScVal::Address(a), ScVal::U64(n)] => Some((a, *n)),
+                            _ => None,
+                        },
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                let Some((stored_address, n)) = stored else {
+                    return Err(self.err(
+                        ScErrorType::Storage,
+                        ScErrorCode::InternalError,
+                        "malformed nonce entry",
+                        &[address.to_val()],
+                    ));
+                };
+                if self.compare(stored_address, &sc_address)? != Ordering::Equal {
+                    return Err(self.err(
+                        ScErrorType::Storage,
+                        ScErrorCode::ExistingValue,
+                        "nonce entry is held by a different address",
+                        &[address.to_val()],
+                    ));
+                }
+                n
+            }
+        };
+        let Some(next) = current.checked_add(1) else {
+            return Err(self.err(
+                ScErrorType::Value,
+                ScErrorCode::ArithDomain,
+                "nonce overflow",
+                &[address.to_val()],
+            ));
+        };
+        let val = ScVal::Vec(Some(ScVec(VecM::try_from(vec![
+            ScVal::Address(sc_address),
+            ScVal::U64(next),
+        ])?)));
+        self.put_contract_data_entry_into_ledger(&key, val, None)?;
+        Ok(current)
+    }
+
+    /// Stages a write of `v` under `k` to storage of type `t`, without
+    /// touching storage. Staged writes belong to the running contract's
+    /// frame: they are applied in order by [`Host::commit_staged`] or
+    /// dropped by [`Host::discard_staged`], and any still staged when the
+    /// frame returns (or is rolled back) are dropped with it. Only
+    /// ledger-backed storage can be staged; instance storage is already
+    /// written back only when the frame returns.
+    // notes on metering: covered by components, plus a charge for the push
+    pub fn stage_contract_data(&self, k: Val, v: Val, t: StorageType) -> Result<(), HostError> {
+        if t == StorageType::Instance {
+            return Err(self.err(
+                ScErrorType::Storage,
+                ScErrorCode::InvalidAction,
+                "instance storage writes can't be staged",
+                &[k],
+            ));
+        }
+        let staged = StagedContractData {
+            key: self.storage_key_from_val(k, t.try_into()?)?,
+            val: self.from_host_val(v)?,
+        };
+        self.charge_budget(
+            ContractCostType::MemAlloc,
+            Some(size_of::<StagedContractData>() as u64),
+        )?;
+        self.with_current_context_mut(|ctx| {
+            ctx.staged.push(staged);
+            Ok(())
+        })
+    }
+
+    /// Applies the writes staged in the running contract's frame. Every key
+    /// is checked against the footprint, and every value against the limit
+    /// set by [`Host::set_max_entry_size`], before any is written, so on
+    /// error nothing is written and the writes stay staged.
+    // notes on metering: covered by components
+    pub fn commit_staged(&self) -> Result<(), HostError> {
+        // An oversized value is reported once the context stack is no longer
+        // borrowed, since raising an error records diagnostics about the
+        // running contract.
+        let oversized = self.with_current_context_mut(|ctx| {
+            for staged in ctx.staged.iter() {
+                self.try_borrow_storage_mut()?
+                    .check_write_access(&staged.key, self.as_budget())?;
+                if let Some(len) = self.oversized_entry_len(&staged.val)? {
+                    return Ok(Some(len));
+                }
+            }
+            Ok(None)
+        })?;
+        if let Some(len) = oversized {
+            return Err(self.entry_too_large_error(len, None)?);
+        }
+        // Nothing can fail for a reason the caller could fix past this
+        // point; an error while writing (such as running out of budget) fails
+        // the frame, which rolls back the writes already made.
+        let staged = self.with_current_context_mut(|ctx| Ok(std::mem::take(&mut ctx.staged)))?;
+        for StagedContractData { key, val } in staged {
+            self.put_contract_data_entry_into_ledger(&key, val, None)?;
+        }
+        Ok(())
+    }
+
+    /// Drops the writes staged in the running contract's frame without
+    /// applying them.
+    pub fn discard_staged(&self) -> Result<(), HostError> {
+        self.with_current_context_mut(|ctx| {
+            ctx.staged.clear();
+            Ok(())
+        })
+    }
+
+    /// Limits the size of values written to persistent and temporary
+    /// contract data: from now on, writing a value whose XDR encoding is
+    /// longer than `bytes` fails with `(Storage, ExceededLimit)` and leaves
+    /// storage unchanged.
+    pub fn set_max_entry_size(&self, bytes: usize) -> Result<(), HostError> {
+        *self.try_borrow_max_entry_size_mut()? = Some(bytes);
+        Ok(())
+    }
+
+    // Checks `val` against the limit set by `set_max_entry_size`, if any.
+    // `key_val` is only used for error reporting.
+    fn check_max_entry_size(&self, val: &ScVal, key_val: Option<Val>) -> Result<(), HostError> {
+        match self.oversized_entry_len(val)? {
+            None => Ok(()),
+            Some(len) => Err(self.entry_too_large_error(len, key_val)?),
+        }
+    }
+
+    // As `check_max_entry_size`, for a value that is still a `Val`. It is
+    // only converted when a limit is set, so writes cost the same as before
+    // the limit existed unless an embedder sets one.
+    fn check_max_entry_size_of_val(&self, k: Val, v: Val) -> Result<(), HostError> {
+        if self.try_borrow_max_entry_size()?.is_none() {
+            return Ok(());
+        }
+        self.check_max_entry_size(&self.from_host_val(v)?, Some(k))
+    }
+
+    // Returns the length of the XDR encoding of `val` if it is over the limit
+    // set by `set_max_entry_size`. This doesn't raise the error itself, so it
+    // can be called while the context stack is borrowed.
+    fn oversized_entry_len(&self, val: &ScVal) -> Result<Option<usize>, HostError> {
+        let Some(max) = *self.try_borrow_max_entry_size()? else {
+            return Ok(None);
+        };
+        let mut buf = vec![];
+        metered_write_xdr(self.budget_ref(), val, &mut buf)?;
+        Ok((buf.len() > max).then_some(buf.len()))
+    }
+
+    fn entry_too_large_error(
+        &self,
+        len: usize,
+        key_val: Option<Val>,
+    ) -> Result<HostError, HostError> {
+        let len = self.usize_to_u32val(len)?.to_val();
+        let msg = "contract data value is larger than the maximum entry size";
+        Ok(match key_val {
+            Some(k) => self.err(
+                ScErrorType::Storage,
+                ScErrorCode::ExceededLimit,
+                msg,
+                &[k, len],
+            ),
+            None => self.err(
+                ScErrorType::Storage,
+                ScErrorCode::ExceededLimit,
+                msg,
+                &[len],
+            ),
+        })
+    }
+
     pub(super) fn put_contract_data_into_ledger(
         &self,
         k: Val,
         v: Val,
         t: StorageType,
+    ) -> Result<(), HostError> {
+        self.check_max_entry_size_of_val(k, v)?;
+        self.write_contract_data_into_ledger(k, v, t)
+    }
+
+    // Writes `v` under `k` to ledger-backed storage of type `t` without
+    // checking the entry size.
+    fn write_contract_data_into_ledger(
+        &self,
+        k: Val,
+        v: Val,
+        t: StorageType,
     ) -> Result<(), HostError> {
         let durability: ContractDataDurability = t.try_into()?;
         let key = self.storage_key_from_val(k, durability)?;
@@ -522,6 +847,80 @@ impl Host {
 
         Ok(())
     }
+
+    // Writes `val` as the value of the contract data entry under `key`, for
+    // writes (staged data, nonces) that already have the entry's `ScVal`s.
+    // `key_val` is only used for error reporting.
+    fn put_contract_data_entry_into_ledger(
+        &self,
+        key: &Rc<LedgerKey>,
+        val: ScVal,
+        key_val: Option<Val>,
+    ) -> Result<(), HostError> {
+        let LedgerKey::ContractData(LedgerKeyContractData {
+            contract,
+            key: key_scval,
+            durability,
+        }) = key.as_ref()
+        else {
+            return Err(self.err(
+                ScErrorType::Storage,
+                ScErrorCode::InternalError,
+                "expected contract data ledger key",
+                &[],
+            ));
+        };
+        // Currently the storage stores the whole ledger entries, while this
+        // operation might only modify the internal `ScVal` value. Thus we
+        // need to only overwrite the value in case if there is already an
+        // existing ledger entry value for the key in the storage.
+        if self
+            .try_borrow_storage_mut()?
+            .has_with_host(key, self, key_val)?
+        {
+            let (current, live_until_ledger) = self
+                .try_borrow_storage_mut()?
+                .get_with_live_until_ledger(key, self, key_val)?;
+            let mut current = (*current).metered_clone(self)?;
+            match current.data {
+                LedgerEntryData::ContractData(ref mut entry) => {
+                    entry.val = val;
+                }
+                _ => {
+                    return Err(self.err(
+                        ScErrorType::Storage,
+                        ScErrorCode::InternalError,
+                        "expected DataEntry",
+                        &[],
+                    ));
+                }
+            }
+            self.try_borrow_storage_mut()?.put_with_host(
+                key,
+                &Rc::metered_new(current, self)?,
+                live_until_ledger,
+                self,
+                key_val,
+            )?;
+        } else {
+            let data = ContractDataEntry {
+                contract: contract.metered_clone(self)?,
+                key: key_scval.metered_clone(self)?,
+                val,
+                durability: *durability,
+                ext: ExtensionPoint::V0,
+            };
+            self.try_borrow_storage_mut()?.put_with_host(
+                key,
+                &Host::new_contract_data(self, data)?,
+                Some(self.get_min_live_until_ledger(*durability)?),
+                self,
+                key_val,
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(any(test, feature = "testutils"))]
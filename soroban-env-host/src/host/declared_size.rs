@@ -25,7 +25,7 @@ use crate::{
     AddressObject, Bool, BytesObject, DurationObject, DurationSmall, DurationVal, Error, HostError,
     I128Object, I128Small, I128Val, I256Object, I256Small, I256Val, I32Val, I64Object, I64Small,
     I64Val, MapObject, Object, ScValObject, StringObject, Symbol, SymbolObject, SymbolSmall,
-    SymbolSmallIter, SymbolStr, TimepointObject, TimepointSmall, TimepointVal, U128Object,
+    SymbolSmallIter, SymbolStr, Tag, TimepointObject, TimepointSmall, TimepointVal, U128Object,
     U128Small, U128Val, U256Object, U256Small, U256Val, U32Val, U64Object, U64Small, U64Val, Val,
     VecObject, Void, I256, U256,
 };
@@ -65,6 +65,7 @@ impl_declared_size_type!(&str, 16);
 
 // Val-wrapping types
 impl_declared_size_type!(Val, 8);
+impl_declared_size_type!(Tag, 1);
 impl_declared_size_type!(Void, 8);
 impl_declared_size_type!(Bool, 8);
 impl_declared_size_type!(VecObject, 8);
@@ -351,6 +352,7 @@ mod test {
 
         // Val-wrapping types
         expect!["8"].assert_eq(size_of::<Val>().to_string().as_str());
+        expect!["1"].assert_eq(size_of::<Tag>().to_string().as_str());
         expect!["8"].assert_eq(size_of::<Void>().to_string().as_str());
         expect!["8"].assert_eq(size_of::<Bool>().to_string().as_str());
         expect!["8"].assert_eq(size_of::<VecObject>().to_string().as_str());
@@ -591,6 +593,7 @@ mod test {
 
         // Val-wrapping types
         assert_mem_size_le_declared_size!(Val);
+        assert_mem_size_le_declared_size!(Tag);
         assert_mem_size_le_declared_size!(Void);
         assert_mem_size_le_declared_size!(Bool);
         assert_mem_size_le_declared_size!(VecObject);
@@ -15,6 +15,9 @@ use std::{
 
 use super::metered_clone::MeteredClone;
 
+#[cfg(any(test, feature = "testutils"))]
+const INTERNAL_ERROR_ABORT_MSG: &str = "aborting on internal error";
+
 #[derive(Clone)]
 pub(crate) struct DebugInfo {
     events: Events,
@@ -291,6 +294,8 @@ impl ErrorHandler for Host {
     /// enriches the returned [Error] with [DebugInfo] in the form of a
     /// [Backtrace] and snapshot of the [Events] buffer.
     fn error(&self, error: Error, msg: &str, args: &[Val]) -> HostError {
+        #[cfg(any(test, feature = "testutils"))]
+        self.maybe_abort_on_internal_error(error, msg);
         let mut he = HostError::from(error);
         self.with_debug_mode(|| {
             // We _try_ to take a mutable borrow of the events buffer refcell
@@ -326,6 +331,27 @@ impl Host {
         self.error(error, msg, args)
     }
 
+    #[cfg(any(test, feature = "testutils"))]
+    fn maybe_abort_on_internal_error(&self, error: Error, msg: &str) {
+        if !error.is_type(ScErrorType::Contract) && error.is_code(ScErrorCode::InternalError) {
+            if let Ok(abort) = self.0.abort_on_internal_error.try_borrow() {
+                if *abort {
+                    panic!("{} {:?}: {}", INTERNAL_ERROR_ABORT_MSG, error, msg);
+                }
+            }
+        }
+    }
+
+    // Tells whether a caught panic was raised by `maybe_abort_on_internal_error`,
+    // so that code catching panics from native test contracts can let it
+    // through rather than turn it into an error a `try_call` could recover.
+    #[cfg(any(test, feature = "testutils"))]
+    pub(crate) fn is_internal_error_abort(payload: &(dyn std::any::Any + Send)) -> bool {
+        payload
+            .downcast_ref::<String>()
+            .is_some_and(|s| s.starts_with(INTERNAL_ERROR_ABORT_MSG))
+    }
+
     pub(crate) fn maybe_get_debug_info(&self) -> Option<Box<DebugInfo>> {
         #[allow(unused_mut)]
         let mut res = None;
@@ -9,7 +9,8 @@ use crate::{
     storage::{InstanceStorageMap, StorageMap},
     xdr::{
         ContractExecutable, ContractIdPreimage, CreateContractArgsV2, Hash, HostFunction,
-        HostFunctionType, ScAddress, ScContractInstance, ScErrorCode, ScErrorType, ScVal,
+        HostFunctionType, LedgerKey, ScAddress, ScContractInstance, ScErrorCode, ScErrorType,
+        ScVal,
     },
     AddressObject, Error, ErrorHandler, Host, HostError, Object, Symbol, SymbolStr, TryFromVal,
     TryIntoVal, Val, Vm, DEFAULT_HOST_DEPTH_LIMIT,
@@ -94,6 +95,17 @@ pub(crate) struct Context {
     pub(crate) frame: Frame,
     pub(crate) prng: Option<Prng>,
     pub(crate) storage: Option<InstanceStorageMap>,
+    pub(crate) staged: Vec<StagedContractData>,
+}
+
+/// A contract data write staged by [`Host::stage_contract_data`] in the
+/// [`Context`] that holds it. The ledger key, which names the contract and
+/// durability, is resolved when the write is staged, and the value is held
+/// as an [`ScVal`] rather than a frame-relative [`Val`].
+#[derive(Clone, Hash)]
+pub(crate) struct StagedContractData {
+    pub(crate) key: Rc<LedgerKey>,
+    pub(crate) val: ScVal,
 }
 
 pub(crate) struct CallParams {
@@ -426,6 +438,7 @@ impl Host {
             frame,
             prng: None,
             storage: None,
+            staged: Vec::new(),
         };
         let rp = self.push_context(ctx)?;
         {
@@ -595,6 +608,22 @@ impl Host {
         })
     }
 
+    /// Inspects the frame at the top of the context and returns the Wasm hash
+    /// of its contract's executable. Returns `Ok(None)` if the context stack is
+    /// empty, has a non-contract frame on top, or the contract on top is not a
+    /// Wasm contract.
+    pub(crate) fn get_current_contract_wasm_hash_opt_internal(
+        &self,
+    ) -> Result<Option<Hash>, HostError> {
+        self.with_current_frame_opt(|opt_frame| match opt_frame.and_then(|f| f.instance()) {
+            Some(ScContractInstance {
+                executable: ContractExecutable::Wasm(wasm_hash),
+                ..
+            }) => Ok(Some(wasm_hash.metered_clone(self)?)),
+            _ => Ok(None),
+        })
+    }
+
     /// Returns [`Hash`] contract ID from the VM frame at the top of the context
     /// stack, or a [`HostError`] if the context stack is empty or has a non-VM
     /// frame at its top.
@@ -616,6 +645,67 @@ impl Host {
         }
     }
 
+    /// Returns the contract IDs of all the contract frames on the context
+    /// stack, outermost first. Frames without a contract ID (such as
+    /// top-level host function frames) are skipped.
+    pub(crate) fn get_call_stack_contract_ids_internal(&self) -> Result<Vec<Hash>, HostError> {
+        let contexts = self.try_borrow_context_stack()?;
+        let mut ids = Vec::<Hash>::with_metered_capacity(contexts.len(), self)?;
+        for ctx in contexts.iter() {
+            if let Some(id) = ctx.frame.contract_id() {
+                ids.push(id.metered_clone(self)?);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Calls `func` with `args` on the currently-executing contract, as a
+    /// callback on behalf of a host function such as `vec_partition`. This is
+    /// an immediate self-call, so it is made in
+    /// [`ContractReentryMode::SelfAllowed`].
+    pub(crate) fn call_current_contract_callback(
+        &self,
+        func: Symbol,
+        args: &[Val],
+    ) -> Result<Val, HostError> {
+        let Some(id) = self.get_current_contract_id_opt_internal()? else {
+            return Err(self.err(
+                ScErrorType::Context,
+                ScErrorCode::InvalidAction,
+                "callback functions can only be called from a contract",
+                &[func.to_val()],
+            ));
+        };
+        self.call_n_internal(
+            &id,
+            func,
+            args,
+            CallParams {
+                reentry_mode: ContractReentryMode::SelfAllowed,
+                internal_host_call: false,
+                treat_missing_function_as_noop: false,
+            },
+        )
+    }
+
+    /// Like [`Host::call_current_contract_callback`], for callbacks that are
+    /// predicates: fails if `func` returns anything other than a `Bool`.
+    pub(crate) fn call_current_contract_predicate(
+        &self,
+        func: Symbol,
+        args: &[Val],
+    ) -> Result<bool, HostError> {
+        let res = self.call_current_contract_callback(func, args)?;
+        bool::try_from(res).map_err(|_| {
+            self.err(
+                ScErrorType::Value,
+                ScErrorCode::UnexpectedType,
+                "predicate function returned a non-bool value",
+                &[func.to_val(), res],
+            )
+        })
+    }
+
     /// Pushes a test contract [`Frame`], runs a closure, and then pops the
     /// frame, rolling back if the closure returned an error. Returns the result
     /// that the closure returned (or any error caused during the frame
@@ -801,6 +891,62 @@ impl Host {
         }
     }
 
+    /// Returns whether the Wasm contract `contract_id` exports a function
+    /// named `func`, loading its module from the module cache or storage.
+    /// Returns an error if the contract is not a Wasm contract.
+    pub fn contract_exports(&self, contract_id: &Hash, func: &Symbol) -> Result<bool, HostError> {
+        let storage_key = self.contract_instance_ledger_key(contract_id)?;
+        let instance = self.retrieve_contract_instance_from_storage(&storage_key)?;
+        match &instance.executable {
+            ContractExecutable::Wasm(wasm_hash) => {
+                let vm = self.instantiate_vm(contract_id, wasm_hash)?;
+                let func_ss: SymbolStr = func.try_into_val(self)?;
+                Ok(vm.module.has_func_export(func_ss.as_ref()))
+            }
+            ContractExecutable::StellarAsset => Err(self.err(
+                ScErrorType::Value,
+                ScErrorCode::UnexpectedType,
+                "contract is not a Wasm contract",
+                &[],
+            )),
+        }
+    }
+
+    /// Returns the number of arguments the function `func` of the Wasm
+    /// contract `contract_id` takes, loading its module from the module cache
+    /// or storage. Returns an error if the contract is not a Wasm contract or
+    /// doesn't export `func`.
+    pub fn contract_function_arity(
+        &self,
+        contract_id: &Hash,
+        func: &Symbol,
+    ) -> Result<u32, HostError> {
+        let storage_key = self.contract_instance_ledger_key(contract_id)?;
+        let instance = self.retrieve_contract_instance_from_storage(&storage_key)?;
+        match &instance.executable {
+            ContractExecutable::Wasm(wasm_hash) => {
+                let vm = self.instantiate_vm(contract_id, wasm_hash)?;
+                let func_ss: SymbolStr = func.try_into_val(self)?;
+                vm.module
+                    .func_export_arity(func_ss.as_ref())
+                    .ok_or_else(|| {
+                        self.err(
+                            ScErrorType::WasmVm,
+                            ScErrorCode::MissingValue,
+                            "contract does not export function",
+                            &[func.to_val()],
+                        )
+                    })
+            }
+            ContractExecutable::StellarAsset => Err(self.err(
+                ScErrorType::Value,
+                ScErrorCode::UnexpectedType,
+                "contract is not a Wasm contract",
+                &[],
+            )),
+        }
+    }
+
     // Notes on metering: this is covered by the called components.
     pub(crate) fn call_n_internal(
         &self,
@@ -919,6 +1065,12 @@ impl Host {
                             }
                         }
                         Err(panic_payload) => {
+                            // An abort requested by `set_abort_on_internal_error`
+                            // has to reach the embedder, so it is not turned
+                            // into an error like other panics below.
+                            if Host::is_internal_error_abort(&*panic_payload) {
+                                std::panic::resume_unwind(panic_payload);
+                            }
                             // Return an error indicating the contract function
                             // panicked.
                             //
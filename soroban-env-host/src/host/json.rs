@@ -0,0 +1,524 @@
+//! A canonical JSON rendering of host values, meant for tooling and logging
+//! outside contracts.
+//!
+//! The rendering is deterministic: equal values always render to the same
+//! text, with no insignificant whitespace. Values map to JSON as follows:
+//!
+//! - `Void` is `null`, and `Bool`s are `true` and `false`.
+//! - `U32Val`s are bare JSON numbers.
+//! - Every other scalar is a JSON string of the form `"<type>:<text>"`:
+//!   `i32`, `u64`, `i64`, `timepoint`, `duration`, `u128`, `i128`, `u256` and
+//!   `i256` in decimal; `sym` and `str` followed by their characters; `bytes`
+//!   in lowercase hex; and `address` as a strkey. For example `"i64:-5"`,
+//!   `"sym:hello"` or `"bytes:00ff"`.
+//! - Vectors are arrays.
+//! - Maps are objects, in key order. A key that renders as a JSON string is
+//!   used as it is; any other key is replaced by the string holding its JSON
+//!   text, so the map `{1: 2}` is `{"1":2}`.
+//!
+//! Errors, and strings that aren't valid UTF-8, have no rendering.
+//!
+//! [`Host::from_json`] parses the same dialect back. It accepts insignificant
+//! whitespace and map entries in any order, but rejects anything else that
+//! isn't in the canonical form, such as other JSON numbers, numbers with
+//! leading zeros or a `+` sign, duplicate map keys, keys spelled as the JSON
+//! text of a string rather than as the string itself, and key text that isn't
+//! exactly as `to_json` renders it (such as `" 1"` for `1`).
+//!
+//! This is only built for testing and recording mode: nothing on the
+//! contract execution path uses it.
+
+use std::str::FromStr;
+
+use crate::{
+    budget::DepthLimiter,
+    num::{i256_from_pieces, i256_into_pieces, u256_from_pieces, u256_into_pieces},
+    xdr::{
+        int128_helpers, AccountId, Duration, Hash, Int128Parts, Int256Parts, PublicKey, ScAddress,
+        ScErrorCode, ScErrorType, ScVal, TimePoint, UInt128Parts, UInt256Parts, Uint256,
+    },
+    Env, EnvBase, Host, HostError, Symbol, TryFromVal, Val, I256, U256,
+};
+
+impl Host {
+    /// Renders `v` as JSON, in the canonical form described in the
+    /// [module documentation](self).
+    ///
+    /// Converting `v` out of the host is metered, as any conversion to XDR
+    /// is; producing the text is not.
+    pub fn to_json(&self, v: Val) -> Result<String, HostError> {
+        let scv = self.from_host_val(v)?;
+        let mut out = String::new();
+        self.write_json(&scv, &mut out)?;
+        Ok(out)
+    }
+
+    /// Parses JSON in the canonical form described in the
+    /// [module documentation](self) into a host value, so that
+    /// `to_json(from_json(s)?)` gives back `s` for any canonical `s`.
+    /// Malformed or non-canonical input is an error.
+    ///
+    /// Building the resulting host objects is metered; parsing the text is
+    /// not.
+    pub fn from_json(&self, s: &str) -> Result<Val, HostError> {
+        let mut parser = JsonParser {
+            host: self,
+            text: s.as_bytes(),
+            pos: 0,
+        };
+        let v = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos != parser.text.len() {
+            return Err(parser.malformed("unexpected characters after JSON value"));
+        }
+        Ok(v)
+    }
+
+    fn write_json(&self, v: &ScVal, out: &mut String) -> Result<(), HostError> {
+        match v {
+            ScVal::Void => out.push_str("null"),
+            ScVal::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+            ScVal::U32(u) => out.push_str(&u.to_string()),
+            ScVal::Vec(Some(vec)) => {
+                out.push('[');
+                for (i, x) in vec.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    self.write_json(x, out)?;
+                }
+                out.push(']');
+            }
+            ScVal::Map(Some(map)) => {
+                out.push('{');
+                for (i, entry) in map.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    let key = match &entry.key {
+                        ScVal::Void
+                        | ScVal::Bool(_)
+                        | ScVal::U32(_)
+                        | ScVal::Vec(_)
+                        | ScVal::Map(_) => {
+                            let mut key = String::new();
+                            self.write_json(&entry.key, &mut key)?;
+                            key
+                        }
+                        k => self.json_tagged_string(k)?,
+                    };
+                    write_json_string(&key, out);
+                    out.push(':');
+                    self.write_json(&entry.val, out)?;
+                }
+                out.push('}');
+            }
+            _ => write_json_string(&self.json_tagged_string(v)?, out),
+        }
+        Ok(())
+    }
+
+    // The `"<type>:<text>"` form of the scalars that aren't JSON literals.
+    fn json_tagged_string(&self, v: &ScVal) -> Result<String, HostError> {
+        let utf8 = |bytes: &[u8]| {
+            std::str::from_utf8(bytes).map_err(|_| {
+                self.err(
+                    ScErrorType::Value,
+                    ScErrorCode::InvalidInput,
+                    "string is not valid UTF-8 and has no JSON rendering",
+                    &[],
+                )
+            })
+        };
+        Ok(match v {
+            ScVal::I32(i) => format!("i32:{i}"),
+            ScVal::U64(u) => format!("u64:{u}"),
+            ScVal::I64(i) => format!("i64:{i}"),
+            ScVal::Timepoint(t) => format!("timepoint:{}", t.0),
+            ScVal::Duration(d) => format!("duration:{}", d.0),
+            ScVal::U128(UInt128Parts { hi, lo }) => {
+                format!("u128:{}", int128_helpers::u128_from_pieces(*hi, *lo))
+            }
+            ScVal::I128(Int128Parts { hi, lo }) => {
+                format!("i128:{}", int128_helpers::i128_from_pieces(*hi, *lo))
+            }
+            ScVal::U256(UInt256Parts {
+                hi_hi,
+                hi_lo,
+                lo_hi,
+                lo_lo,
+            }) => format!("u256:{}", u256_from_pieces(*hi_hi, *hi_lo, *lo_hi, *lo_lo)),
+            ScVal::I256(Int256Parts {
+                hi_hi,
+                hi_lo,
+                lo_hi,
+                lo_lo,
+            }) => format!("i256:{}", i256_from_pieces(*hi_hi, *hi_lo, *lo_hi, *lo_lo)),
+            ScVal::Symbol(s) => format!("sym:{}", utf8(s.as_slice())?),
+            ScVal::String(s) => format!("str:{}", utf8(s.as_slice())?),
+            ScVal::Bytes(b) => {
+                let mut s = String::from("bytes:");
+                for byte in b.iter() {
+                    s.push_str(&format!("{byte:02x}"));
+                }
+                s
+            }
+            ScVal::Address(ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(
+                Uint256(key),
+            )))) => format!("address:{}", stellar_strkey::ed25519::PublicKey(*key)),
+            ScVal::Address(ScAddress::Contract(Hash(h))) => {
+                format!("address:{}", stellar_strkey::Contract(*h))
+            }
+            _ => {
+                return Err(self.err(
+                    ScErrorType::Value,
+                    ScErrorCode::UnexpectedType,
+                    "value has no JSON rendering",
+                    &[],
+                ))
+            }
+        })
+    }
+}
+
+fn write_json_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+// The type prefixes of the scalars rendered as `"<type>:<text>"`.
+const JSON_TAGS: &[&str] = &[
+    "i32",
+    "u64",
+    "i64",
+    "timepoint",
+    "duration",
+    "u128",
+    "i128",
+    "u256",
+    "i256",
+    "sym",
+    "str",
+    "bytes",
+    "address",
+];
+
+struct JsonParser<'a> {
+    host: &'a Host,
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn malformed(&self, msg: &'static str) -> HostError {
+        self.host
+            .err(ScErrorType::Value, ScErrorCode::InvalidInput, msg, &[])
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.text.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, s: &str) -> Result<(), HostError> {
+        if self.text[self.pos..].starts_with(s.as_bytes()) {
+            self.pos += s.len();
+            Ok(())
+        } else {
+            Err(self.malformed("unexpected character in JSON"))
+        }
+    }
+
+    fn value(&mut self) -> Result<Val, HostError> {
+        self.skip_whitespace();
+        match self.text.get(self.pos) {
+            Some(b'n') => {
+                self.expect("null")?;
+                Ok(Val::VOID.to_val())
+            }
+            Some(b't') => {
+                self.expect("true")?;
+                Ok(true.into())
+            }
+            Some(b'f') => {
+                self.expect("false")?;
+                Ok(false.into())
+            }
+            Some(b'0'..=b'9') => self.number(),
+            Some(b'"') => {
+                let s = self.string()?;
+                self.host
+                    .json_tagged_scalar(&s)?
+                    .ok_or_else(|| self.malformed("JSON string has no known type prefix"))
+            }
+            Some(b'[') => self
+                .host
+                .budget_cloned()
+                .with_limited_depth(|_| self.array()),
+            Some(b'{') => self
+                .host
+                .budget_cloned()
+                .with_limited_depth(|_| self.object()),
+            _ => Err(self.malformed("unexpected character in JSON")),
+        }
+    }
+
+    // Bare numbers are `U32Val`s.
+    fn number(&mut self) -> Result<Val, HostError> {
+        let start = self.pos;
+        while let Some(b'0'..=b'9') = self.text.get(self.pos) {
+            self.pos += 1;
+        }
+        if let Some(b'.' | b'e' | b'E') = self.text.get(self.pos) {
+            return Err(self.malformed("only unsigned 32-bit integers can be bare JSON numbers"));
+        }
+        let digits = std::str::from_utf8(&self.text[start..self.pos])
+            .map_err(|_| self.malformed("malformed JSON number"))?;
+        Ok(self.host.json_canonical_num::<u32>(digits)?.into())
+    }
+
+    fn string(&mut self) -> Result<String, HostError> {
+        self.expect("\"")?;
+        let mut out = Vec::new();
+        loop {
+            let Some(&b) = self.text.get(self.pos) else {
+                return Err(self.malformed("unterminated JSON string"));
+            };
+            self.pos += 1;
+            match b {
+                b'"' => break,
+                b'\\' => {
+                    let c = match self.text.get(self.pos) {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.pos += 1;
+                            let hi = self.hex4()?;
+                            let code = if (0xd800..0xdc00).contains(&hi) {
+                                self.expect("\\u")?;
+                                let lo = self.hex4()?;
+                                if !(0xdc00..0xe000).contains(&lo) {
+                                    return Err(self.malformed("unpaired surrogate in JSON string"));
+                                }
+                                0x10000 + ((hi - 0xd800) << 10) + (lo - 0xdc00)
+                            } else {
+                                hi
+                            };
+                            let c = char::from_u32(code).ok_or_else(|| {
+                                self.malformed("unpaired surrogate in JSON string")
+                            })?;
+                            out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                            continue;
+                        }
+                        _ => return Err(self.malformed("unknown escape in JSON string")),
+                    };
+                    self.pos += 1;
+                    out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                0..=0x1f => {
+                    return Err(self.malformed("unescaped control character in JSON string"))
+                }
+                _ => out.push(b),
+            }
+        }
+        // The input was a `str` and escapes only split it at ASCII characters,
+        // so this can't fail.
+        String::from_utf8(out).map_err(|_| self.malformed("JSON string is not valid UTF-8"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, HostError> {
+        let digits = self
+            .text
+            .get(self.pos..self.pos + 4)
+            .and_then(|d| std::str::from_utf8(d).ok())
+            .and_then(|d| u32::from_str_radix(d, 16).ok())
+            .ok_or_else(|| self.malformed("malformed \\u escape in JSON string"))?;
+        self.pos += 4;
+        Ok(digits)
+    }
+
+    fn array(&mut self) -> Result<Val, HostError> {
+        self.expect("[")?;
+        let mut vals = Vec::new();
+        self.skip_whitespace();
+        if self.text.get(self.pos) == Some(&b']') {
+            self.pos += 1;
+        } else {
+            loop {
+                vals.push(self.value()?);
+                self.skip_whitespace();
+                if self.text.get(self.pos) == Some(&b',') {
+                    self.pos += 1;
+                } else {
+                    self.expect("]")?;
+                    break;
+                }
+            }
+        }
+        Ok(self.host.vec_new_from_slice(&vals)?.to_val())
+    }
+
+    fn object(&mut self) -> Result<Val, HostError> {
+        self.expect("{")?;
+        let mut map = self.host.map_new()?;
+        self.skip_whitespace();
+        if self.text.get(self.pos) == Some(&b'}') {
+            self.pos += 1;
+            return Ok(map.to_val());
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            let key = match self.host.json_tagged_scalar(&key)? {
+                Some(k) => k,
+                // Keys that aren't scalar strings are the JSON text of the key,
+                // exactly as `to_json` renders it, so that no key has a second
+                // spelling: `{" 1":2}` is not `{"1":2}`. That text mustn't
+                // itself be a string, which would be a second spelling of a
+                // key that has one already.
+                None if !key.starts_with('"') => {
+                    let k = self.host.from_json(&key)?;
+                    if self.host.to_json(k)? != key {
+                        return Err(self.malformed("JSON map key is not in canonical form"));
+                    }
+                    k
+                }
+                None => return Err(self.malformed("JSON map key is not in canonical form")),
+            };
+            self.skip_whitespace();
+            self.expect(":")?;
+            let val = self.value()?;
+            if self.host.map_has(map, key)?.into() {
+                return Err(self.malformed("duplicate key in JSON map"));
+            }
+            map = self.host.map_put(map, key, val)?;
+            self.skip_whitespace();
+            if self.text.get(self.pos) == Some(&b',') {
+                self.pos += 1;
+            } else {
+                self.expect("}")?;
+                return Ok(map.to_val());
+            }
+        }
+    }
+}
+
+impl Host {
+    // Parses `text` as a number of type `T`, accepting only the form that
+    // `to_json` renders it in.
+    fn json_canonical_num<T: FromStr + ToString>(&self, text: &str) -> Result<T, HostError> {
+        match text.parse::<T>() {
+            Ok(n) if n.to_string() == text => Ok(n),
+            _ => Err(self.err(
+                ScErrorType::Value,
+                ScErrorCode::InvalidInput,
+                "JSON number is malformed, out of range or not in canonical form",
+                &[],
+            )),
+        }
+    }
+
+    // Converts a `"<type>:<text>"` string back to the scalar it renders, or
+    // returns `None` if `s` has no known type prefix.
+    fn json_tagged_scalar(&self, s: &str) -> Result<Option<Val>, HostError> {
+        let Some((tag, text)) = s.split_once(':').filter(|(tag, _)| JSON_TAGS.contains(tag)) else {
+            return Ok(None);
+        };
+        let malformed = |msg| self.err(ScErrorType::Value, ScErrorCode::InvalidInput, msg, &[]);
+        let scv = match tag {
+            "i32" => ScVal::I32(self.json_canonical_num(text)?),
+            "u64" => ScVal::U64(self.json_canonical_num(text)?),
+            "i64" => ScVal::I64(self.json_canonical_num(text)?),
+            "timepoint" => ScVal::Timepoint(TimePoint(self.json_canonical_num(text)?)),
+            "duration" => ScVal::Duration(Duration(self.json_canonical_num(text)?)),
+            "u128" => {
+                let u: u128 = self.json_canonical_num(text)?;
+                ScVal::U128(UInt128Parts {
+                    hi: int128_helpers::u128_hi(u),
+                    lo: int128_helpers::u128_lo(u),
+                })
+            }
+            "i128" => {
+                let i: i128 = self.json_canonical_num(text)?;
+                ScVal::I128(Int128Parts {
+                    hi: int128_helpers::i128_hi(i),
+                    lo: int128_helpers::i128_lo(i),
+                })
+            }
+            "u256" => {
+                let (hi_hi, hi_lo, lo_hi, lo_lo) =
+                    u256_into_pieces(self.json_canonical_num::<U256>(text)?);
+                ScVal::U256(UInt256Parts {
+                    hi_hi,
+                    hi_lo,
+                    lo_hi,
+                    lo_lo,
+                })
+            }
+            "i256" => {
+                let (hi_hi, hi_lo, lo_hi, lo_lo) =
+                    i256_into_pieces(self.json_canonical_num::<I256>(text)?);
+                ScVal::I256(Int256Parts {
+                    hi_hi,
+                    hi_lo,
+                    lo_hi,
+                    lo_lo,
+                })
+            }
+            "sym" => return Ok(Some(Symbol::try_from_val(self, &text)?.to_val())),
+            "str" => return Ok(Some(self.string_new_from_slice(text.as_bytes())?.to_val())),
+            "bytes" => {
+                let digits = text.as_bytes();
+                if digits.len() % 2 != 0
+                    || !digits
+                        .iter()
+                        .all(|c| matches!(c, b'0'..=b'9' | b'a'..=b'f'))
+                {
+                    return Err(malformed(
+                        "JSON bytes must be an even number of lowercase hex digits",
+                    ));
+                }
+                let nibble = |c: u8| if c <= b'9' { c - b'0' } else { c - b'a' + 10 };
+                let bytes: Vec<u8> = digits
+                    .chunks_exact(2)
+                    .map(|pair| (nibble(pair[0]) << 4) | nibble(pair[1]))
+                    .collect();
+                return Ok(Some(self.bytes_new_from_slice(&bytes)?.to_val()));
+            }
+            "address" => {
+                let addr = match stellar_strkey::Strkey::from_string(text) {
+                    Ok(stellar_strkey::Strkey::PublicKeyEd25519(pk)) => ScAddress::Account(
+                        AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(pk.0))),
+                    ),
+                    Ok(stellar_strkey::Strkey::Contract(c)) => ScAddress::Contract(Hash(c.0)),
+                    _ => {
+                        return Err(malformed(
+                            "JSON address is not an account or contract strkey",
+                        ))
+                    }
+                };
+                return Ok(Some(self.add_host_object(addr)?.to_val()));
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(self.to_host_val(&scv)?))
+    }
+}
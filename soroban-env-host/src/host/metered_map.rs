@@ -5,7 +5,7 @@ use crate::{
     Compare, Error, Host, HostError,
 };
 
-use std::{borrow::Borrow, cmp::Ordering, marker::PhantomData};
+use std::{borrow::Borrow, cmp::Ordering, marker::PhantomData, ops::Range};
 
 use super::metered_vector::binary_search_by_pre_rust_182;
 
@@ -195,7 +195,39 @@ where
 
     pub fn insert(&self, key: K, value: V, ctx: &Ctx) -> Result<Self, HostError> {
         self.charge_access(1, ctx)?;
-        match self.find(&key, ctx)? {
+        let found = self.find(&key, ctx)?;
+        self.insert_at(found, key, value, ctx)
+    }
+
+    /// Like [`insert`](Self::insert), but computes the new value for `key`
+    /// from its current value (or `None` if absent) with `f`, locating the
+    /// key only once.
+    pub fn update<F>(&self, key: K, f: F, ctx: &Ctx) -> Result<Self, HostError>
+    where
+        F: FnOnce(Option<&V>) -> Result<V, HostError>,
+    {
+        self.charge_access(1, ctx)?;
+        let found = self.find(&key, ctx)?;
+        let value = match found {
+            Ok(pos) => {
+                let Some((_, v)) = self.map.get(pos) else {
+                    return Err((ScErrorType::Object, ScErrorCode::InternalError).into());
+                };
+                f(Some(v))?
+            }
+            Err(_) => f(None)?,
+        };
+        self.insert_at(found, key, value, ctx)
+    }
+
+    fn insert_at(
+        &self,
+        found: Result<usize, usize>,
+        key: K,
+        value: V,
+        ctx: &Ctx,
+    ) -> Result<Self, HostError> {
+        match found {
             Ok(replace_pos) => {
                 // [0,1,2] replace_pos == 1
                 // take(1) + new + skip(2)
@@ -287,6 +319,19 @@ where
         }
     }
 
+    /// Returns the range of positions of the entries whose keys `k` satisfy
+    /// `lo <= k < hi`, located by binary search on both bounds rather than by
+    /// scanning. The range is empty if `hi <= lo`.
+    pub fn index_range<Q>(&self, lo: &Q, hi: &Q, ctx: &Ctx) -> Result<Range<usize>, HostError>
+    where
+        K: Borrow<Q>,
+        Ctx: Compare<Q, Error = HostError>,
+    {
+        let (Ok(start) | Err(start)) = self.find(lo, ctx)?;
+        let (Ok(end) | Err(end)) = self.find(hi, ctx)?;
+        Ok(start..end.max(start))
+    }
+
     pub fn len(&self) -> usize {
         self.map.len()
     }
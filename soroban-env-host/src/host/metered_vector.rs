@@ -311,6 +311,13 @@ where
         self.vec.iter()
     }
 
+    // Like `iter`, but charges up front for visiting every element. Cost of
+    // any work done per element is charged separately and not covered here.
+    pub fn metered_iter(&self, budget: &Budget) -> Result<std::slice::Iter<'_, A>, HostError> {
+        self.charge_scan(budget)?;
+        Ok(self.vec.iter())
+    }
+
     pub fn to_vec(&self, budget: &Budget) -> Result<Vec<A>, HostError> {
         self.vec.metered_clone(budget)
     }
@@ -36,6 +36,7 @@ pub mod vm;
 pub use vm::{CompilationContext, ModuleCache, Vm};
 pub mod storage;
 pub use budget::{DEFAULT_HOST_DEPTH_LIMIT, DEFAULT_XDR_RW_LIMITS};
+pub use builtin_contracts::base_types::I256 as HostI256;
 pub use host::{
     metered_map::MeteredOrdMap, metered_vector::MeteredVector, ErrorHandler, Host, HostError, Seed,
     SEED_BYTES,
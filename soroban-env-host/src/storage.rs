@@ -573,6 +573,25 @@ impl Storage {
         }
     }
 
+    /// Checks that `key` may be written, without writing it: always succeeds
+    /// in [FootprintMode::Recording] mode, and in [FootprintMode::Enforcing]
+    /// mode succeeds only if `key` has been declared in the [Footprint] as
+    /// [AccessType::ReadWrite].
+    pub(crate) fn check_write_access(
+        &mut self,
+        key: &Rc<LedgerKey>,
+        budget: &Budget,
+    ) -> Result<(), HostError> {
+        Self::check_supported_ledger_key_type(key)?;
+        match self.mode {
+            FootprintMode::Recording(_) => Ok(()),
+            FootprintMode::Enforcing => {
+                self.footprint
+                    .enforce_access(key, AccessType::ReadWrite, budget)
+            }
+        }
+    }
+
     fn prepare_read_only_access(
         &mut self,
         key: &Rc<LedgerKey>,
@@ -168,3 +168,41 @@ fn invalid_strkey_to_address_conversion() {
         ))
         .is_err());
 }
+
+#[cfg(feature = "next")]
+#[test]
+fn test_account_id_from_ed25519() {
+    use soroban_env_common::{
+        xdr::{ScErrorCode, ScErrorType},
+        EnvBase,
+    };
+    let host = Host::test_host();
+    let account_pk = [
+        0x3f, 0x0c, 0x34, 0xbf, 0x93, 0xad, 0x0d, 0x99, 0x71, 0xd0, 0x4c, 0xcc, 0x90, 0xf7, 0x05,
+        0x51, 0x1c, 0x83, 0x8a, 0xad, 0x97, 0x34, 0xa4, 0xa2, 0xfb, 0x0d, 0x7a, 0x03, 0xfc, 0x7f,
+        0xe8, 0x9a,
+    ];
+    let pubkey = host.bytes_new_from_slice(&account_pk).unwrap();
+    let address_obj = host.account_id_from_ed25519(pubkey).unwrap();
+    host.visit_obj(address_obj, |addr: &ScAddress| {
+        assert_eq!(
+            addr,
+            &ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(
+                account_pk
+            ))))
+        );
+        Ok(())
+    })
+    .unwrap();
+    let strkey = host.address_to_strkey(address_obj).unwrap();
+    assert_eq!(
+        extract_string(&host, strkey),
+        "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ"
+    );
+
+    let short = host.bytes_new_from_slice(&account_pk[..31]).unwrap();
+    assert!(crate::HostError::result_matches_err(
+        host.account_id_from_ed25519(short),
+        (ScErrorType::Object, ScErrorCode::UnexpectedSize)
+    ));
+}
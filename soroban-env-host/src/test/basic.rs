@@ -87,3 +87,201 @@ fn f32_does_not_work() -> Result<(), HostError> {
     ));
     Ok(())
 }
+
+#[cfg(feature = "next")]
+#[test]
+fn error_type_and_code_accessors() -> Result<(), HostError> {
+    use crate::{
+        xdr::{ScErrorCode, ScErrorType},
+        Env, Error,
+    };
+    let host = Host::test_host();
+
+    let e = Error::from_type_and_code(ScErrorType::Storage, ScErrorCode::MissingValue);
+    let ty: u32 = host.error_type(e.to_val())?.into();
+    let code: u32 = host.error_code(e.to_val())?.into();
+    assert_eq!(ty, ScErrorType::Storage as u32);
+    assert_eq!(code, ScErrorCode::MissingValue as u32);
+
+    let e = Error::from_contract_error(12345);
+    let ty: u32 = host.error_type(e.to_val())?.into();
+    let code: u32 = host.error_code(e.to_val())?.into();
+    assert_eq!(ty, ScErrorType::Contract as u32);
+    assert_eq!(code, 12345);
+
+    assert!(HostError::result_matches_err(
+        host.error_type(1u32.into()),
+        (ScErrorType::Value, ScErrorCode::UnexpectedType)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn error_from_type_and_code() -> Result<(), HostError> {
+    use crate::{
+        xdr::{ScErrorCode, ScErrorType},
+        Env, Error, U32Val,
+    };
+    let host = Host::test_host();
+    let from_parts =
+        |ty: u32, code: u32| host.error_from_type_and_code(U32Val::from(ty), U32Val::from(code));
+
+    let e = from_parts(ScErrorType::Object as u32, ScErrorCode::IndexBounds as u32)?;
+    let expected = Error::from_type_and_code(ScErrorType::Object, ScErrorCode::IndexBounds);
+    assert_eq!(e.to_val().get_payload(), expected.to_val().get_payload());
+    let ty: u32 = host.error_type(e.to_val())?.into();
+    let code: u32 = host.error_code(e.to_val())?.into();
+    assert_eq!(ty, ScErrorType::Object as u32);
+    assert_eq!(code, ScErrorCode::IndexBounds as u32);
+
+    // Contract errors take any code.
+    let e = from_parts(ScErrorType::Contract as u32, 12345)?;
+    assert_eq!(
+        e.to_val().get_payload(),
+        Error::from_contract_error(12345).to_val().get_payload()
+    );
+
+    // Unknown types, and unknown codes for host error types, are rejected.
+    for (ty, code) in [
+        (ScErrorType::Auth as u32 + 1, 0),
+        (u32::MAX, 0),
+        (
+            ScErrorType::Object as u32,
+            ScErrorCode::UnexpectedSize as u32 + 1,
+        ),
+        (ScErrorType::Object as u32, u32::MAX),
+    ] {
+        assert!(HostError::result_matches_err(
+            from_parts(ty, code),
+            (ScErrorType::Value, ScErrorCode::InvalidInput)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn obj_new_empty_by_type() -> Result<(), HostError> {
+    use crate::{
+        xdr::{ScErrorCode, ScErrorType, ScValType},
+        BytesObject, Env, MapObject, StringObject, U32Val, VecObject,
+    };
+    let host = Host::test_host();
+    let new_empty = |ty: ScValType| host.obj_new_empty(U32Val::from(ty as u32));
+
+    let v = VecObject::try_from(new_empty(ScValType::Vec)?)?;
+    assert_eq!(u32::from(host.vec_len(v)?), 0);
+    let m = MapObject::try_from(new_empty(ScValType::Map)?)?;
+    assert_eq!(u32::from(host.map_len(m)?), 0);
+    let b = BytesObject::try_from(new_empty(ScValType::Bytes)?)?;
+    assert_eq!(u32::from(host.bytes_len(b)?), 0);
+    let s = StringObject::try_from(new_empty(ScValType::String)?)?;
+    assert_eq!(u32::from(host.string_len(s)?), 0);
+
+    // Types with no empty form, and non-types, are rejected.
+    let code = (ScErrorType::Value, ScErrorCode::InvalidInput);
+    assert!(HostError::result_matches_err(
+        new_empty(ScValType::U64),
+        code
+    ));
+    assert!(HostError::result_matches_err(
+        host.obj_new_empty(U32Val::from(1000)),
+        code
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn val_select_ct_selects_by_bool() -> Result<(), HostError> {
+    use crate::{
+        xdr::{ScErrorCode, ScErrorType},
+        Env, U32Val,
+    };
+    let host = Host::test_host();
+
+    let (a, b) = (U32Val::from(1).to_val(), U32Val::from(2).to_val());
+    assert!(host.val_select_ct(true.into(), a, b)?.shallow_eq(&a));
+    assert!(host.val_select_ct(false.into(), a, b)?.shallow_eq(&b));
+
+    // Objects are selected by handle.
+    let (va, vb) = (host.vec_new()?.to_val(), host.map_new()?.to_val());
+    assert!(host.val_select_ct(true.into(), va, vb)?.shallow_eq(&va));
+    assert!(host.val_select_ct(false.into(), va, vb)?.shallow_eq(&vb));
+
+    let code = (ScErrorType::Value, ScErrorCode::UnexpectedType);
+    assert!(HostError::result_matches_err(
+        host.val_select_ct(a, a, b),
+        code
+    ));
+    assert!(HostError::result_matches_err(
+        host.val_select_ct(Val::VOID.to_val(), a, b),
+        code
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn result_ok_err_round_trip() -> Result<(), HostError> {
+    use crate::{
+        xdr::{ScErrorCode, ScErrorType},
+        Env, EnvBase, Error, Symbol, U32Val, VecObject,
+    };
+    let host = Host::test_host();
+    let value = U32Val::from(7).to_val();
+    let error = Error::from_contract_error(3);
+    let ok = host.result_ok(value)?;
+    let err = host.result_err(error)?;
+
+    // Results survive serialization like any other vector.
+    let round_trip = |r: VecObject| -> Result<VecObject, HostError> {
+        let bytes = host.serialize_to_bytes(r.to_val())?;
+        Ok(host.deserialize_from_bytes(bytes)?.try_into()?)
+    };
+    let (ok, err) = (round_trip(ok)?, round_trip(err)?);
+
+    assert!(bool::from(host.result_is_ok(ok)?));
+    assert!(!bool::from(host.result_is_ok(err)?));
+    assert!(host.result_unwrap(ok)?.shallow_eq(&value));
+    // Unwrapping a failed result fails with the error it holds.
+    assert!(HostError::result_matches_err(
+        host.result_unwrap(err),
+        error
+    ));
+
+    // Vectors that aren't results are rejected.
+    let other = host.vec_new_from_slice(&[Symbol::try_from_small_str("Some")?.to_val(), value])?;
+    assert!(HostError::result_matches_err(
+        host.result_is_ok(other),
+        (ScErrorType::Value, ScErrorCode::UnexpectedType)
+    ));
+    let bad_err = host.vec_new_from_slice(&[Symbol::try_from_small_str("Err")?.to_val(), value])?;
+    assert!(HostError::result_matches_err(
+        host.result_unwrap(bad_err),
+        (ScErrorType::Value, ScErrorCode::UnexpectedType)
+    ));
+    assert!(HostError::result_matches_err(
+        host.result_is_ok(host.vec_new()?),
+        (ScErrorType::Object, ScErrorCode::UnexpectedSize)
+    ));
+
+    // Only contract errors can be raised, whether the failed result is made
+    // by `result_err` or by hand.
+    let internal = Error::from_type_and_code(ScErrorType::Context, ScErrorCode::InternalError);
+    let code = (ScErrorType::Context, ScErrorCode::UnexpectedType);
+    assert!(HostError::result_matches_err(
+        host.result_err(internal),
+        code
+    ));
+    let forged = host.vec_new_from_slice(&[
+        Symbol::try_from_small_str("Err")?.to_val(),
+        internal.to_val(),
+    ])?;
+    assert!(HostError::result_matches_err(
+        host.result_unwrap(forged),
+        code
+    ));
+    Ok(())
+}
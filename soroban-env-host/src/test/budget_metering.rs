@@ -5,11 +5,11 @@ use crate::{
         metered_xdr::metered_write_xdr,
     },
     xdr::{ContractCostType, ScMap, ScMapEntry, ScVal},
-    Env, ErrorHandler, Host, HostError, Symbol, Val,
+    Env, EnvBase, ErrorHandler, Host, HostError, Symbol, Val,
 };
 use expect_test::{self, expect};
 use soroban_env_common::xdr::{ScErrorCode, ScErrorType};
-use soroban_test_wasms::VEC;
+use soroban_test_wasms::{ADD_I32, INVOKE_CONTRACT, VEC};
 
 #[test]
 fn xdr_object_conversion() -> Result<(), HostError> {
@@ -92,6 +92,215 @@ fn vm_hostfn_invocation() -> Result<(), HostError> {
     Ok(())
 }
 
+// Every `call` that enters a VM frame, including nested cross-contract
+// calls, is charged the frame-setup cost `InvokeVmFunction` exactly once.
+#[test]
+fn vm_frame_setup_charged_once_per_call() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let add_id = host.register_test_contract_wasm(ADD_I32);
+    let invoke_id = host.register_test_contract_wasm(INVOKE_CONTRACT);
+    let frame_calls = |host: &Host| -> Result<u64, HostError> {
+        Ok(host
+            .as_budget()
+            .get_tracker(ContractCostType::InvokeVmFunction)?
+            .iterations)
+    };
+
+    host.as_budget().reset_unlimited()?;
+    for n in 1..=3 {
+        host.call(
+            add_id,
+            Symbol::try_from_small_str("add")?,
+            host.test_vec_obj::<i32>(&[1, 2])?,
+        )?;
+        assert_eq!(frame_calls(&host)?, n);
+    }
+
+    host.as_budget().reset_unlimited()?;
+    let args = host.test_vec_obj::<i32>(&[5, 6])?;
+    let args = host.vec_push_back(args, add_id.to_val())?;
+    host.call(invoke_id, Symbol::try_from_small_str("add_with")?, args)?;
+    assert_eq!(frame_calls(&host)?, 2);
+    Ok(())
+}
+
+#[test]
+fn measure_reports_budget_delta() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let map = host.map_new()?;
+    let bytes = host.bytes_new_from_slice(&[1; 100])?;
+    let cpu_before = host.as_budget().get_cpu_insns_consumed()?;
+
+    let (res, delta) = host.measure(|| -> Result<_, HostError> {
+        let map = host.map_put(map, 1u32.into(), 2u32.into())?;
+        host.compute_hash_sha256(bytes)?;
+        Ok(map)
+    })?;
+    res?;
+    let sha = delta.get_tracker(ContractCostType::ComputeSha256Hash);
+    assert_eq!(sha.iterations, 1);
+    assert_eq!(sha.inputs, Some(100));
+    assert!(sha.cpu > 0);
+    assert!(delta.get_tracker(ContractCostType::MemAlloc).cpu > 0);
+    assert!(delta.cpu_insns > 0);
+    // Measuring doesn't reset the budget's running totals.
+    assert_eq!(
+        host.as_budget().get_cpu_insns_consumed()?,
+        cpu_before + delta.cpu_insns
+    );
+
+    let ((), delta) = host.measure(|| ())?;
+    assert_eq!(delta.cpu_insns, 0);
+    assert_eq!(delta.get_tracker(ContractCostType::MemAlloc).iterations, 0);
+    Ok(())
+}
+
+#[test]
+fn budget_diff_reports_differing_cost_types() -> Result<(), HostError> {
+    let run = |hashes: usize| -> Result<Host, HostError> {
+        let host = Host::test_host();
+        let bytes = host.bytes_new_from_slice(&[1; 100])?;
+        for _ in 0..hashes {
+            host.compute_hash_sha256(bytes)?;
+        }
+        Ok(host)
+    };
+    let a = run(1)?;
+    let b = run(2)?;
+    let (a, b) = (a.as_budget(), b.as_budget());
+
+    // A budget doesn't differ from itself, nor from one that did the same work.
+    assert!(a.diff(a)?.is_empty());
+    assert!(a.diff(run(1)?.as_budget())?.is_empty());
+
+    // The diff lists exactly the cost types whose trackers differ.
+    let diff = b.diff(a)?;
+    let mut expected = vec![];
+    for ty in ContractCostType::variants() {
+        let (tb, ta) = (b.get_tracker(ty)?, a.get_tracker(ty)?);
+        if tb.cpu != ta.cpu || tb.mem != ta.mem {
+            expected.push((
+                ty,
+                tb.cpu as i64 - ta.cpu as i64,
+                tb.mem as i64 - ta.mem as i64,
+            ));
+        }
+    }
+    assert_eq!(diff, expected);
+
+    // The extra hash accounts for one hash's worth of cpu, and the diff is
+    // antisymmetric.
+    let sha = a.get_tracker(ContractCostType::ComputeSha256Hash)?;
+    assert!(diff.contains(&(
+        ContractCostType::ComputeSha256Hash,
+        sha.cpu as i64,
+        sha.mem as i64
+    )));
+    let reversed: Vec<_> = a
+        .diff(b)?
+        .into_iter()
+        .map(|(ty, cpu, mem)| (ty, -cpu, -mem))
+        .collect();
+    assert_eq!(reversed, diff);
+    Ok(())
+}
+
+#[test]
+fn budget_report_above_keeps_expensive_cost_types() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let bytes = host.bytes_new_from_slice(&[1; 1000])?;
+    for _ in 0..10 {
+        host.compute_hash_sha256(bytes)?;
+    }
+    host.vec_new()?;
+    let budget = host.as_budget();
+
+    // Set the threshold just below what hashing cost.
+    let sha = budget.get_tracker(ContractCostType::ComputeSha256Hash)?.cpu;
+    let threshold = sha - 1;
+    let report = budget.report_above(threshold)?;
+    assert!(report.contains(&(
+        ContractCostType::ComputeSha256Hash,
+        sha,
+        budget.get_tracker(ContractCostType::ComputeSha256Hash)?.mem
+    )));
+
+    // Exactly the cost types above the threshold are listed, most expensive
+    // first. Cheaper cost types that did run are left out.
+    let (mut expected, mut cheap) = (0, 0);
+    for ty in ContractCostType::variants() {
+        let t = budget.get_tracker(ty)?;
+        if t.cpu > threshold {
+            expected += 1;
+        } else {
+            cheap += (t.cpu > 0) as usize;
+            assert!(!report.iter().any(|(r, _, _)| *r == ty));
+        }
+    }
+    assert!(cheap > 0);
+    assert_eq!(report.len(), expected);
+    assert!(report.windows(2).all(|w| w[0].1 >= w[1].1));
+    assert!(budget.report_above(u64::MAX)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn call_with_budget_caps_the_call() -> Result<(), HostError> {
+    use crate::testutils::wasm::wasm_module_with_4n_insns;
+    let host = Host::test_host_with_recording_footprint();
+    let id_obj = host.register_test_contract_wasm(&wasm_module_with_4n_insns(1000));
+    let sym = Symbol::try_from_small_str("test")?;
+    let args = host.test_vec_obj::<u32>(&[10_000])?;
+    let budget = host.as_budget().clone();
+    let limits = || -> Result<(u64, u64), HostError> {
+        Ok((
+            budget.get_cpu_insns_consumed()? + budget.get_cpu_insns_remaining()?,
+            budget.get_mem_bytes_consumed()? + budget.get_mem_bytes_remaining()?,
+        ))
+    };
+    let limits_before = limits()?;
+
+    // With a generous cap the call succeeds.
+    host.call_with_budget(id_obj, sym, args, 10_000_000, 10_000_000)?;
+    assert_eq!(limits()?, limits_before);
+
+    // A tight cpu cap stops it, but the budget beyond the cap is untouched
+    // and what the call consumed is still charged.
+    let cpu_before = budget.get_cpu_insns_consumed()?;
+    assert!(HostError::result_matches_err(
+        host.call_with_budget(id_obj, sym, args, 10_000, 10_000_000),
+        (ScErrorType::Budget, ScErrorCode::ExceededLimit)
+    ));
+    assert_eq!(limits()?, limits_before);
+    assert!(budget.get_cpu_insns_consumed()? > cpu_before);
+    assert!(budget.get_cpu_insns_remaining()? > 0);
+    host.call(id_obj, sym, args)?;
+    Ok(())
+}
+
+#[test]
+fn max_insns_per_call_traps_long_calls() -> Result<(), HostError> {
+    use crate::testutils::wasm::wasm_module_with_4n_insns;
+    let host = Host::test_host_with_recording_footprint();
+    let small = host.register_test_contract_wasm(&wasm_module_with_4n_insns(10));
+    let large = host.register_test_contract_wasm(&wasm_module_with_4n_insns(10_000));
+    let sym = Symbol::try_from_small_str("test")?;
+    let args = host.test_vec_obj::<u32>(&[1])?;
+    host.call(large, sym, args)?;
+
+    host.set_max_insns_per_call(1_000)?;
+    host.call(small, sym, args)?;
+    assert!(HostError::result_matches_err(
+        host.call(large, sym, args),
+        (ScErrorType::Budget, ScErrorCode::ExceededLimit)
+    ));
+    // The budget could have paid for the call, and each call gets the full
+    // limit, so the small contract keeps running.
+    assert!(host.as_budget().get_cpu_insns_remaining()? > 1_000_000);
+    host.call(small, sym, args)?;
+    Ok(())
+}
+
 #[test]
 fn test_vm_fuel_metering() -> Result<(), HostError> {
     use crate::testutils::wasm::wasm_module_with_4n_insns;
@@ -536,3 +745,31 @@ fn total_amount_charged_from_random_inputs() -> Result<(), HostError> {
 
     Ok(())
 }
+
+#[cfg(feature = "next")]
+#[test]
+fn reserve_budget_checks_without_consuming() -> Result<(), HostError> {
+    use crate::{TryFromVal, U64Val};
+    let host = Host::test_host();
+    host.as_budget().reset_limits(1_000_000, 1_000_000)?;
+    let v = |x: u64| -> Result<U64Val, HostError> { Ok(U64Val::try_from_val(&host, &x)?) };
+    let reserve = |cpu: u64, mem: u64| -> Result<bool, HostError> {
+        let (cpu, mem) = (v(cpu)?, v(mem)?);
+        Ok(host.reserve_budget(cpu, mem)?.into())
+    };
+
+    assert!(reserve(0, 0)?);
+    assert!(reserve(100_000, 100_000)?);
+    assert!(!reserve(2_000_000, 0)?);
+    assert!(!reserve(0, 2_000_000)?);
+    assert!(!reserve(u64::MAX, u64::MAX)?);
+
+    // Checking doesn't consume the amounts checked for.
+    let cpu_remaining = host.as_budget().get_cpu_insns_remaining()?;
+    let (cpu, mem) = (v(cpu_remaining)?, v(0)?);
+    let mem_remaining = host.as_budget().get_mem_bytes_remaining()?;
+    assert!(bool::from(host.reserve_budget(cpu, mem)?));
+    assert_eq!(host.as_budget().get_cpu_insns_remaining()?, cpu_remaining);
+    assert_eq!(host.as_budget().get_mem_bytes_remaining()?, mem_remaining);
+    Ok(())
+}
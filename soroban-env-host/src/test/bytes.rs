@@ -1,4 +1,6 @@
 use crate::Symbol;
+#[cfg(feature = "next")]
+use crate::U64Val;
 use crate::{
     budget::AsBudget,
     testutils::wasm,
@@ -527,3 +529,313 @@ fn instantiate_oversized_bytes_from_linear_memory() -> Result<(), HostError> {
 
     Ok(())
 }
+
+#[test]
+fn deserialize_with_object_interning() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let v = host.test_vec_val(&[1u32, 2, 3])?;
+    let bo = host.serialize_to_bytes(v)?;
+
+    // Off by default: identical values get distinct handles.
+    let a = host.deserialize_from_bytes(bo)?;
+    let b = host.deserialize_from_bytes(bo)?;
+    assert_ne!(a.get_payload(), b.get_payload());
+
+    host.enable_object_interning()?;
+    let c = host.deserialize_from_bytes(bo)?;
+    let d = host.deserialize_from_bytes(bo)?;
+    assert_eq!(c.get_payload(), d.get_payload());
+    assert_eq!(host.obj_cmp(c, v)?, 0);
+
+    // A different value still gets its own handle.
+    let other = host.test_vec_val(&[4u32])?;
+    let e = host.deserialize_from_bytes(host.serialize_to_bytes(other)?)?;
+    assert_ne!(c.get_payload(), e.get_payload());
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn bytes_pad_start_and_end() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let b = host.test_bin_obj(&[1, 2])?;
+
+    let padded = host.bytes_pad_start(b, 4u32.into(), 0xffu32.into())?;
+    let expected = host.test_bin_obj(&[0xff, 0xff, 1, 2])?;
+    assert_eq!(host.obj_cmp(padded.into(), expected.into())?, 0);
+
+    let padded = host.bytes_pad_end(b, 4u32.into(), 0u32.into())?;
+    let expected = host.test_bin_obj(&[1, 2, 0, 0])?;
+    assert_eq!(host.obj_cmp(padded.into(), expected.into())?, 0);
+
+    // Already long enough: the input is returned as-is.
+    let same = host.bytes_pad_start(b, 2u32.into(), 0u32.into())?;
+    assert_eq!(same.to_val().get_payload(), b.to_val().get_payload());
+    let same = host.bytes_pad_end(b, 1u32.into(), 0u32.into())?;
+    assert_eq!(same.to_val().get_payload(), b.to_val().get_payload());
+
+    let res = host.bytes_pad_end(b, 4u32.into(), 256u32.into());
+    assert!(HostError::result_matches_err(
+        res,
+        (ScErrorType::Value, ScErrorCode::ArithDomain)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn bytes_trim() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let trim = |bytes: &[u8], byte: u32| -> Result<BytesObject, HostError> {
+        host.bytes_trim(host.test_bin_obj(bytes)?, byte.into())
+    };
+    let trimmed = trim(&[0, 0, 5, 0], 0)?;
+    let expected = host.test_bin_obj(&[5])?;
+    assert_eq!(host.obj_cmp(trimmed.into(), expected.into())?, 0);
+    let trimmed = trim(&[32, 1, 32, 2, 32, 32], 32)?;
+    let expected = host.test_bin_obj(&[1, 32, 2])?;
+    assert_eq!(host.obj_cmp(trimmed.into(), expected.into())?, 0);
+
+    // Nothing to trim: the input is returned as-is.
+    let b = host.test_bin_obj(&[1, 0, 2])?;
+    let same = host.bytes_trim(b, 0u32.into())?;
+    assert_eq!(same.to_val().get_payload(), b.to_val().get_payload());
+
+    // Only trim bytes: the result is empty.
+    assert_eq!(u32::from(host.bytes_len(trim(&[7, 7, 7], 7)?)?), 0);
+    assert_eq!(u32::from(host.bytes_len(trim(&[], 7)?)?), 0);
+
+    assert!(HostError::result_matches_err(
+        trim(&[1], 256),
+        (ScErrorType::Value, ScErrorCode::ArithDomain)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn bytes_read_be_integers() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let b = host.test_bin_obj(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a])?;
+
+    assert_eq!(
+        u32::from(host.bytes_read_u32_be(b, 0u32.into())?),
+        0x01020304
+    );
+    assert_eq!(
+        u32::from(host.bytes_read_u32_be(b, 6u32.into())?),
+        0x0708090a
+    );
+    let v = host.bytes_read_u64_be(b, 0u32.into())?;
+    assert_eq!(u64::try_from_val(&host, &v)?, 0x0102030405060708);
+    let v = host.bytes_read_u64_be(b, 2u32.into())?;
+    assert_eq!(u64::try_from_val(&host, &v)?, 0x030405060708090a);
+
+    let oob = (ScErrorType::Object, ScErrorCode::IndexBounds);
+    assert!(HostError::result_matches_err(
+        host.bytes_read_u32_be(b, 7u32.into()),
+        oob
+    ));
+    assert!(HostError::result_matches_err(
+        host.bytes_read_u64_be(b, 3u32.into()),
+        oob
+    ));
+    assert!(HostError::result_matches_err(
+        host.bytes_read_u32_be(b, u32::MAX.into()),
+        oob
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn bytes_write_be_integers() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let b = host.test_bin_obj(&[0; 10])?;
+
+    let res = host.bytes_write_u32_be(b, 0u32.into(), 0x01020304u32.into())?;
+    let expected = host.test_bin_obj(&[1, 2, 3, 4, 0, 0, 0, 0, 0, 0])?;
+    assert_eq!(host.obj_cmp(res.into(), expected.into())?, 0);
+
+    let v = U64Val::try_from_val(&host, &0x0102030405060708u64)?;
+    let res = host.bytes_write_u64_be(b, 2u32.into(), v)?;
+    let expected = host.test_bin_obj(&[0, 0, 1, 2, 3, 4, 5, 6, 7, 8])?;
+    assert_eq!(host.obj_cmp(res.into(), expected.into())?, 0);
+
+    // The input is unchanged and the result is never grown.
+    let zeros = host.test_bin_obj(&[0; 10])?;
+    assert_eq!(host.obj_cmp(b.into(), zeros.into())?, 0);
+    let oob = (ScErrorType::Object, ScErrorCode::IndexBounds);
+    assert!(HostError::result_matches_err(
+        host.bytes_write_u32_be(b, 7u32.into(), 1u32.into()),
+        oob
+    ));
+    assert!(HostError::result_matches_err(
+        host.bytes_write_u64_be(b, 3u32.into(), v),
+        oob
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn bytes_push_with_index() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let b = host.bytes_new_from_slice(&[0; 8])?;
+    let res = host.bytes_push_with_index(b, U32Val::from(0xab))?;
+    assert_eq!(u32::from(host.vec_len(res)?), 2);
+    let bnew: BytesObject = host.vec_get(res, U32Val::from(0))?.try_into()?;
+    let index: U32Val = host.vec_get(res, U32Val::from(1))?.try_into()?;
+    assert_eq!(u32::from(index), 8);
+    assert_eq!(u32::from(host.bytes_len(bnew)?), 9);
+    assert_eq!(u32::from(host.bytes_get(bnew, index)?), 0xab);
+    // The original is unchanged.
+    assert_eq!(u32::from(host.bytes_len(b)?), 8);
+
+    assert!(HostError::result_matches_err(
+        host.bytes_push_with_index(b, U32Val::from(256)),
+        (ScErrorType::Value, ScErrorCode::ArithDomain)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn bytes_find() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let hay = host.bytes_new_from_slice(b"abcabcde")?;
+    let find = |needle: &[u8], start: u32| -> Result<Val, HostError> {
+        let needle = host.bytes_new_from_slice(needle)?;
+        host.bytes_find(hay, needle, U32Val::from(start))
+    };
+    let at = |i: u32| U32Val::from(i).to_val();
+
+    // Found mid-buffer, and the search honors `start`.
+    assert!(find(b"cab", 0)?.shallow_eq(&at(2)));
+    assert!(find(b"abc", 1)?.shallow_eq(&at(3)));
+    assert!(find(b"de", 0)?.shallow_eq(&at(6)));
+
+    // Not present.
+    assert!(find(b"abd", 0)?.is_void());
+    assert!(find(b"abc", 4)?.is_void());
+    assert!(find(b"abcabcdef", 0)?.is_void());
+
+    // An empty needle is found at `start`.
+    assert!(find(b"", 5)?.shallow_eq(&at(5)));
+    assert!(find(b"", 8)?.shallow_eq(&at(8)));
+
+    // A `start` past the end finds nothing.
+    assert!(find(b"a", 9)?.is_void());
+    assert!(find(b"", 9)?.is_void());
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn bytes_starts_and_ends_with() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let b = host.bytes_new_from_slice(b"\0asm\x01\0\0\0")?;
+    let starts = |p: &[u8]| -> Result<bool, HostError> {
+        Ok(host
+            .bytes_starts_with(b, host.bytes_new_from_slice(p)?)?
+            .into())
+    };
+    let ends = |s: &[u8]| -> Result<bool, HostError> {
+        Ok(host
+            .bytes_ends_with(b, host.bytes_new_from_slice(s)?)?
+            .into())
+    };
+
+    assert!(starts(b"\0asm")?);
+    assert!(ends(b"\x01\0\0\0")?);
+    assert!(!starts(b"asm")?);
+    assert!(!ends(b"\x01\0\0")?);
+
+    // Empty and whole-buffer affixes match; longer ones don't.
+    assert!(starts(b"")?);
+    assert!(ends(b"")?);
+    assert!(starts(b"\0asm\x01\0\0\0")?);
+    assert!(ends(b"\0asm\x01\0\0\0")?);
+    assert!(!starts(b"\0asm\x01\0\0\0\0")?);
+    assert!(!ends(b"\0\0asm\x01\0\0\0")?);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn bytes_xor() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let bytes = |b: &[u8]| host.bytes_new_from_slice(b);
+
+    let a = bytes(&[0x0f, 0xf0, 0xaa, 0x00])?;
+    let res = host.bytes_xor(a, bytes(&[0xff, 0xff, 0x55, 0x12])?)?;
+    let expected = bytes(&[0xf0, 0x0f, 0xff, 0x12])?;
+    assert_eq!(host.obj_cmp(res.into(), expected.into())?, 0);
+
+    // XOR with zeros gives back the original, and with itself gives zeros.
+    let res = host.bytes_xor(a, bytes(&[0; 4])?)?;
+    assert_eq!(host.obj_cmp(res.into(), a.into())?, 0);
+    let res = host.bytes_xor(a, a)?;
+    assert_eq!(host.obj_cmp(res.into(), bytes(&[0; 4])?.into())?, 0);
+
+    assert!(HostError::result_matches_err(
+        host.bytes_xor(a, bytes(&[0; 3])?),
+        (ScErrorType::Value, ScErrorCode::InvalidInput)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn bytes_rle_round_trip() -> Result<(), HostError> {
+    use rand::{RngCore, SeedableRng};
+    let host = Host::test_host();
+    let round_trip = |b: &[u8]| -> Result<BytesObject, HostError> {
+        let obj = host.bytes_new_from_slice(b)?;
+        let compressed = host.bytes_rle_compress(obj)?;
+        let res = host.bytes_rle_decompress(compressed)?;
+        assert_eq!(host.obj_cmp(res.into(), obj.into())?, 0);
+        Ok(compressed)
+    };
+
+    // Runs longer than 255 bytes are split.
+    let mut repetitive = vec![0; 1000];
+    repetitive.extend([1; 10]);
+    let compressed = round_trip(&repetitive)?;
+    let expected = host.bytes_new_from_slice(&[255, 0, 255, 0, 255, 0, 235, 0, 10, 1])?;
+    assert_eq!(host.obj_cmp(compressed.into(), expected.into())?, 0);
+
+    // Data without runs grows, but still round-trips.
+    let mut random = [0; 256];
+    rand_chacha::ChaCha20Rng::seed_from_u64(0).fill_bytes(&mut random);
+    let compressed = round_trip(&random)?;
+    assert!(u32::from(host.bytes_len(compressed)?) > 256);
+    let compressed = round_trip(&[])?;
+    assert_eq!(u32::from(host.bytes_len(compressed)?), 0);
+
+    // An odd length or a zero count is malformed.
+    for malformed in [&[1, 2, 3][..], &[0, 7]] {
+        let obj = host.bytes_new_from_slice(malformed)?;
+        assert!(HostError::result_matches_err(
+            host.bytes_rle_decompress(obj),
+            (ScErrorType::Value, ScErrorCode::InvalidInput)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn bytes_crc32() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let crc = |b: &[u8]| -> Result<u32, HostError> {
+        Ok(host.bytes_crc32(host.bytes_new_from_slice(b)?)?.into())
+    };
+    assert_eq!(crc(b"123456789")?, 0xcbf4_3926);
+    assert_eq!(
+        crc(b"The quick brown fox jumps over the lazy dog")?,
+        0x414f_a339
+    );
+    assert_eq!(crc(b"")?, 0);
+    Ok(())
+}
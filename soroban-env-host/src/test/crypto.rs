@@ -419,3 +419,68 @@ fn test_secp256r1_signature_verification() -> Result<(), HostError> {
 
     Ok(())
 }
+
+#[cfg(feature = "next")]
+#[test]
+fn merkle_root_sha256_test() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let root = |n: u8| -> Result<String, HostError> {
+        let mut leaves = host.vec_new()?;
+        for i in 0..n {
+            let leaf = host.bytes_new_from_slice(&[i; 32])?;
+            leaves = host.vec_push_back(leaves, leaf.to_val())?;
+        }
+        let root = host.merkle_root_sha256(leaves)?;
+        Ok(host.hash_from_bytesobj_input("root", root)?.0.encode_hex())
+    };
+
+    // Leaves are [0; 32], [1; 32], ..., so with 4 leaves the root is
+    // sha256(sha256(l0 || l1) || sha256(l2 || l3)).
+    assert_eq!(
+        root(4)?,
+        "d35f51699389da7eec7ce5eb02640c6d318cf51ae39eca890bbc7b84ecb5da68"
+    );
+    // With 3 leaves the last one is paired with itself:
+    // sha256(sha256(l0 || l1) || sha256(l2 || l2)).
+    assert_eq!(
+        root(3)?,
+        "6defcff843b12dd684162580c341065283869e4b2e09ea9a27c19d997462a53c"
+    );
+    // A single leaf is its own root.
+    assert_eq!(root(1)?, [0u8; 32].encode_hex::<String>());
+
+    assert!(is_crypto_error(root(0).unwrap_err()));
+    let bad_leaf = host.test_vec_obj::<u32>(&[1])?;
+    assert!(host.merkle_root_sha256(bad_leaf).is_err());
+    let short_leaf = host.bytes_new_from_slice(&[0; 31])?;
+    let leaves = host.vec_new_from_slice(&[short_leaf.to_val()])?;
+    assert!(is_object_error(
+        host.merkle_root_sha256(leaves).unwrap_err()
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn ed25519_pubkey_is_valid_test() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let is_valid = |k: &[u8]| -> Result<bool, HostError> {
+        let k = host.bytes_new_from_slice(k)?;
+        Ok(host.ed25519_pubkey_is_valid(k)?.into())
+    };
+
+    // Public key from https://datatracker.ietf.org/doc/html/rfc8032#section-7.1
+    let rfc_key =
+        hex::decode("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+    assert!(is_valid(&rfc_key)?);
+
+    // y = 2 is not the y-coordinate of any point on the curve.
+    let mut not_on_curve = [0u8; 32];
+    not_on_curve[0] = 2;
+    assert!(!is_valid(&not_on_curve)?);
+
+    // Keys of the wrong length are invalid rather than an error.
+    assert!(!is_valid(&rfc_key[..31])?);
+    assert!(!is_valid(&[])?);
+    Ok(())
+}
@@ -137,3 +137,39 @@ fn length_limited_scval_xdr_conversion() -> Result<(), HostError> {
     assert!(HostError::result_matches_err(res, code));
     Ok(())
 }
+
+#[test]
+fn host_obj_cmp_at_depth_limit() -> Result<(), HostError> {
+    use crate::{EnvBase, VecObject, DEFAULT_HOST_DEPTH_LIMIT};
+    let host = Host::test_host();
+    host.as_budget().reset_unlimited()?;
+
+    // Builds `depth` vectors nested inside one another around a u32 `leaf`.
+    let nested = |depth: u32, leaf: u32| -> Result<VecObject, HostError> {
+        let mut v = host.test_vec_obj::<u32>(&[leaf])?;
+        for _ in 1..depth {
+            v = host.vec_new_from_slice(&[v.to_val()])?;
+        }
+        Ok(v)
+    };
+    let cmp = |a: VecObject, b: VecObject| host.obj_cmp(a.to_val(), b.to_val());
+
+    // Each level of object nesting takes one level of depth, so structures
+    // exactly at the limit still compare, all the way down to their leaves.
+    let depth = DEFAULT_HOST_DEPTH_LIMIT;
+    assert_eq!(cmp(nested(depth, 1)?, nested(depth, 1)?)?, 0);
+    assert_eq!(cmp(nested(depth, 1)?, nested(depth, 2)?)?, -1);
+    assert_eq!(cmp(nested(depth, 2)?, nested(depth, 1)?)?, 1);
+
+    // One more level fails with an error instead of recursing further, even
+    // if the structures are equal.
+    let code = (ScErrorType::Context, ScErrorCode::ExceededLimit);
+    let res = cmp(nested(depth + 1, 1)?, nested(depth + 1, 1)?);
+    assert!(HostError::result_matches_err(res, code));
+    let res = cmp(nested(depth + 1, 1)?, nested(depth + 1, 2)?);
+    assert!(HostError::result_matches_err(res, code));
+
+    // Hitting the limit leaves the depth budget intact for later comparisons.
+    assert_eq!(cmp(nested(depth, 1)?, nested(depth, 1)?)?, 0);
+    Ok(())
+}
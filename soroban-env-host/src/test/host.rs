@@ -47,3 +47,434 @@ fn invalid_val() -> Result<(), HostError> {
 
     Ok(())
 }
+
+#[test]
+fn snapshot_and_restore_objects() -> Result<(), HostError> {
+    use crate::{
+        xdr::{ScVal, ScVec, WriteXdr},
+        EnvBase,
+    };
+    let host = Host::test_host();
+    let bytes = host.test_bin_obj(&[1, 2, 3])?;
+    let big = host.obj_from_u64(u64::MAX)?;
+    let vec = host.vec_new_from_slice(&[bytes.to_val(), big.to_val()])?;
+    let map = host.map_put(host.map_new()?, big.to_val(), vec.to_val())?;
+
+    let snapshot = host.snapshot_objects()?;
+
+    // Build some more objects, then roll back to the snapshot.
+    let _more = host.test_bin_obj(&[4, 5, 6])?;
+    let _more = host.vec_new_from_slice(&[vec.to_val()])?;
+    host.restore_objects(&snapshot)?;
+
+    // The restored handles resolve to the original values, including the
+    // nested references held by the containers.
+    let expected_bytes = host.test_bin_obj(&[1, 2, 3])?;
+    assert_eq!(host.obj_cmp(bytes.into(), expected_bytes.into())?, 0);
+    assert_eq!(host.obj_to_u64(big)?, u64::MAX);
+    let expected_vec = host.vec_new_from_slice(&[expected_bytes.to_val(), big.to_val()])?;
+    assert_eq!(host.obj_cmp(vec.into(), expected_vec.into())?, 0);
+    let got = host.map_get(map, big.to_val())?;
+    assert_eq!(host.obj_cmp(got, expected_vec.into())?, 0);
+
+    // Restoring again from the same snapshot drops everything created since.
+    host.restore_objects(&snapshot)?;
+    assert_eq!(host.snapshot_objects()?, snapshot);
+
+    // A malformed snapshot is rejected and leaves the object table as it was.
+    let bad = ScVal::Vec(Some(ScVec(
+        vec![ScVal::U64(1), ScVal::Bool(true)].try_into().unwrap(),
+    )))
+    .to_xdr(crate::DEFAULT_XDR_RW_LIMITS)
+    .unwrap();
+    assert!(HostError::result_matches_err(
+        host.restore_objects(&bad),
+        (ScErrorType::Value, ScErrorCode::InvalidInput)
+    ));
+    assert_eq!(host.snapshot_objects()?, snapshot);
+    assert_eq!(host.obj_to_u64(big)?, u64::MAX);
+    Ok(())
+}
+
+#[test]
+fn checkpoint_and_rollback() -> Result<(), HostError> {
+    use crate::{xdr::Hash, EnvBase, StorageType, Symbol};
+    use soroban_test_wasms::CONTRACT_STORAGE;
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    let key: Val = Symbol::try_from_small_str("k")?.into();
+    let has_key = || -> Result<bool, HostError> {
+        let res =
+            host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("has")?, || {
+                Ok(host.has_contract_data(key, StorageType::Persistent)?.into())
+            })?;
+        Ok(bool::try_from(res)?)
+    };
+    let objects = || -> Result<usize, HostError> {
+        Ok(host.object_type_counts()?.iter().map(|(_, n)| n).sum())
+    };
+    let kept = host.bytes_new_from_slice(&[1, 2, 3])?;
+
+    let cp = host.checkpoint()?;
+    let (cpu, mem) = (
+        host.as_budget().get_cpu_insns_consumed()?,
+        host.as_budget().get_mem_bytes_consumed()?,
+    );
+    let (n_objects, n_events) = (objects()?, host.get_events()?.0.len());
+
+    // Write storage, allocate objects and emit an event.
+    let mut dropped = None;
+    host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("put")?, || {
+        host.put_contract_data(key, key, StorageType::Persistent)?;
+        let topics = host.vec_new_from_slice(&[key])?;
+        host.contract_event(topics, key)?;
+        dropped = Some(host.bytes_new_from_slice(&[4, 5, 6])?);
+        Ok(Val::VOID.into())
+    })?;
+    assert!(has_key()?);
+    assert!(objects()? > n_objects);
+    assert!(host.get_events()?.0.len() > n_events);
+
+    host.rollback(cp)?;
+    assert_eq!(host.as_budget().get_cpu_insns_consumed()?, cpu);
+    assert_eq!(host.as_budget().get_mem_bytes_consumed()?, mem);
+    assert_eq!(objects()?, n_objects);
+    assert_eq!(host.get_events()?.0.len(), n_events);
+    assert!(!has_key()?);
+
+    // Objects from before the checkpoint survive; newer ones are gone.
+    assert!(dropped.is_some_and(|b| host.bytes_len(b).is_err()));
+    let expected = host.bytes_new_from_slice(&[1, 2, 3])?;
+    assert_eq!(host.obj_cmp(kept.to_val(), expected.to_val())?, 0);
+
+    // A checkpoint can only be rolled back to at the depth it was taken.
+    let mut inner = None;
+    host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("cp")?, || {
+        inner = Some(host.checkpoint()?);
+        Ok(Val::VOID.into())
+    })?;
+    assert!(HostError::result_matches_err(
+        host.rollback(inner.unwrap()),
+        (ScErrorType::Context, ScErrorCode::InvalidAction)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn rollback_restores_staged_writes() -> Result<(), HostError> {
+    use crate::{xdr::Hash, StorageType, Symbol};
+    use soroban_test_wasms::CONTRACT_STORAGE;
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    let [a, b] = ["a", "b"].map(|s| Symbol::try_from_small_str(s).unwrap().to_val());
+    let t = StorageType::Persistent;
+
+    host.with_test_contract_frame(id, Symbol::try_from_small_str("put")?, || {
+        host.stage_contract_data(a, Val::from_u32(1).into(), t)?;
+        let cp = host.checkpoint()?;
+        host.stage_contract_data(b, Val::from_u32(2).into(), t)?;
+        host.rollback(cp)?;
+        // Only the write staged before the checkpoint is left to commit.
+        host.commit_staged()?;
+        assert!(bool::from(host.has_contract_data(a, t)?));
+        assert!(!bool::from(host.has_contract_data(b, t)?));
+        Ok(Val::VOID.into())
+    })?;
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn wall_time_only_in_benchmark_mode() -> Result<(), HostError> {
+    use crate::TryIntoVal;
+    let host = Host::test_host();
+    assert!(HostError::result_matches_err(
+        host.get_wall_time_nanos(),
+        (ScErrorType::Context, ScErrorCode::InvalidAction)
+    ));
+
+    host.enable_benchmark_mode()?;
+    let nanos = |host: &Host| -> Result<u64, HostError> {
+        Ok(host.get_wall_time_nanos()?.try_into_val(host)?)
+    };
+    let first = nanos(&host)?;
+    std::thread::sleep(std::time::Duration::from_millis(2));
+    let second = nanos(&host)?;
+    assert!(second >= first + 2_000_000);
+    Ok(())
+}
+
+#[test]
+fn abort_on_internal_error() -> Result<(), HostError> {
+    use crate::{
+        testutils::call_with_suppressed_panic_hook,
+        xdr::{Hash, ScAddress},
+        Compare, ContractFunctionSet, EnvBase, Symbol,
+    };
+    use std::{panic::AssertUnwindSafe, rc::Rc};
+
+    let host = Host::test_host();
+    let raise_internal = || {
+        host.err(
+            ScErrorType::Context,
+            ScErrorCode::InternalError,
+            "injected internal error",
+            &[],
+        )
+    };
+
+    // By default an internal error is returned as an ordinary (if
+    // non-recoverable) `HostError` that the embedder can inspect.
+    let err = raise_internal();
+    assert!(err.error.is_code(ScErrorCode::InternalError));
+    assert!(!err.is_recoverable());
+
+    // With the flag set, raising it panics, so nothing can absorb it.
+    host.set_abort_on_internal_error(true)?;
+    let res = call_with_suppressed_panic_hook(AssertUnwindSafe(raise_internal));
+    assert!(res.is_err());
+    // Errors other than internal ones are unaffected.
+    let err = host.err(
+        ScErrorType::Value,
+        ScErrorCode::InvalidInput,
+        "not internal",
+        &[],
+    );
+    assert!(err.is_recoverable());
+
+    host.set_abort_on_internal_error(false)?;
+    assert!(!raise_internal().is_recoverable());
+
+    // An internal error raised inside a contract called through `try_call`
+    // is not recovered either way: it fails the `try_call` by default, and
+    // with the flag set the abort panic escapes the native frame's panic
+    // handling instead of being turned into an error.
+    struct RaiseInternalError;
+    impl ContractFunctionSet for RaiseInternalError {
+        fn call(&self, func: &Symbol, host: &Host, _args: &[Val]) -> Option<Val> {
+            if host
+                .compare(
+                    &host.symbol_new_from_slice(b"__constructor").unwrap().into(),
+                    func,
+                )
+                .unwrap()
+                .is_eq()
+            {
+                return Some(().into());
+            }
+            let err = host.err(
+                ScErrorType::Context,
+                ScErrorCode::InternalError,
+                "injected internal error",
+                &[],
+            );
+            host.escalate_error_to_panic(err)
+        }
+    }
+    let addr = host.add_host_object(ScAddress::Contract(Hash([0; 32])))?;
+    host.register_test_contract(addr, Rc::new(RaiseInternalError))?;
+    let func = Symbol::try_from_small_str("go")?;
+    let args = host.vec_new()?;
+    let res = host.try_call(addr, func, args);
+    assert!(HostError::result_matches_err(
+        res,
+        (ScErrorType::Context, ScErrorCode::InternalError)
+    ));
+    host.set_abort_on_internal_error(true)?;
+    let res = call_with_suppressed_panic_hook(AssertUnwindSafe(|| host.try_call(addr, func, args)));
+    assert!(res.is_err());
+    Ok(())
+}
+
+#[test]
+fn live_memory_bytes_tracks_objects_and_storage() -> Result<(), HostError> {
+    use crate::{xdr::Hash, EnvBase, StorageType, Symbol};
+    use soroban_test_wasms::CONTRACT_STORAGE;
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+
+    // New objects add at least their contents.
+    let before = host.live_memory_bytes()?;
+    let big = host.bytes_new_from_slice(&[0; 10_000])?;
+    let with_object = host.live_memory_bytes()?;
+    assert!(with_object >= before + 10_000);
+
+    let key: Val = Symbol::try_from_small_str("big")?.into();
+    host.with_test_contract_frame(id, Symbol::try_from_small_str("put")?, || {
+        host.put_contract_data(key, big.into(), StorageType::Persistent)?;
+        Ok(Val::VOID.into())
+    })?;
+    let with_entry = host.live_memory_bytes()?;
+    assert!(with_entry >= with_object + 10_000);
+    let mem_with_entry = host.budget_cloned().get_mem_bytes_consumed()?;
+
+    // Deleting the entry gives its memory back, though the budget's
+    // cumulative memory count never goes down.
+    host.with_test_contract_frame(id, Symbol::try_from_small_str("del")?, || {
+        host.del_contract_data(key, StorageType::Persistent)?;
+        Ok(Val::VOID.into())
+    })?;
+    let after_del = host.live_memory_bytes()?;
+    assert!(after_del + 10_000 <= with_entry);
+    assert!(host.budget_cloned().get_mem_bytes_consumed()? >= mem_with_entry);
+    Ok(())
+}
+
+#[test]
+fn object_type_counts_counts_live_objects() -> Result<(), HostError> {
+    use crate::{EnvBase, Tag};
+    let host = Host::test_host();
+    let count = |tag| -> Result<usize, HostError> {
+        Ok(host
+            .object_type_counts()?
+            .into_iter()
+            .find(|(t, _)| *t == tag)
+            .map_or(0, |(_, n)| n))
+    };
+    let (vecs, maps, bytes) = (
+        count(Tag::VecObject)?,
+        count(Tag::MapObject)?,
+        count(Tag::BytesObject)?,
+    );
+
+    host.vec_new()?;
+    host.vec_new_from_slice(&[1_u32.into()])?;
+    host.map_new()?;
+    for b in [&[][..], &[1], &[1, 2]] {
+        host.bytes_new_from_slice(b)?;
+    }
+    assert_eq!(count(Tag::VecObject)?, vecs + 2);
+    assert_eq!(count(Tag::MapObject)?, maps + 1);
+    assert_eq!(count(Tag::BytesObject)?, bytes + 3);
+
+    // Types without live objects are left out and the rest come in tag order.
+    let counts = host.object_type_counts()?;
+    assert!(counts.iter().all(|(_, n)| *n > 0));
+    assert!(counts.windows(2).all(|w| w[0].0 < w[1].0));
+    Ok(())
+}
+
+#[test]
+fn for_each_storage_entry_visits_and_stops_early() -> Result<(), HostError> {
+    use crate::{xdr::Hash, EnvBase, StorageType, Symbol};
+    use core::ops::ControlFlow;
+    use soroban_test_wasms::CONTRACT_STORAGE;
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    host.with_test_contract_frame(id, Symbol::try_from_small_str("put")?, || {
+        for k in ["a", "b", "c"] {
+            let key: Val = Symbol::try_from_small_str(k)?.into();
+            host.put_contract_data(key, key, StorageType::Persistent)?;
+        }
+        Ok(Val::VOID.into())
+    })?;
+
+    // The contract's code and instance entries are visited along with the
+    // three data entries.
+    let mut all = 0;
+    host.for_each_storage_entry(|_, _| {
+        all += 1;
+        ControlFlow::Continue(())
+    })?;
+    assert!(all >= 5);
+
+    let mut first = 0;
+    let cpu_before = host.budget_cloned().get_cpu_insns_consumed()?;
+    host.for_each_storage_entry(|_, _| {
+        first += 1;
+        ControlFlow::Break(())
+    })?;
+    assert_eq!(first, 1);
+    assert!(host.budget_cloned().get_cpu_insns_consumed()? > cpu_before);
+    Ok(())
+}
+
+#[test]
+fn host_call_count_counts_contract_calls() -> Result<(), HostError> {
+    use crate::{testutils::wasm, Symbol};
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(&wasm::wasm_module_with_five_host_calls());
+
+    // Native calls are not counted.
+    let before = host.host_call_count()?;
+    let args = host.vec_new()?;
+    assert_eq!(host.host_call_count()?, before);
+
+    let res = host.call(contract, Symbol::try_from_small_str("test")?, args)?;
+    assert_eq!(u32::try_from(res)?, 3);
+    assert_eq!(host.host_call_count()? - before, 5);
+
+    host.call(contract, Symbol::try_from_small_str("test")?, args)?;
+    assert_eq!(host.host_call_count()? - before, 10);
+    Ok(())
+}
+
+#[test]
+fn object_shape_counts_depth_and_nodes() -> Result<(), HostError> {
+    use crate::{Symbol, DEFAULT_HOST_DEPTH_LIMIT};
+    let host = Host::test_host();
+    let u = |x: u32| soroban_env_common::U32Val::from(x).to_val();
+
+    // A scalar is a single node at depth 0.
+    assert_eq!(host.object_shape(u(1))?, (0, 1));
+
+    // [{a: 1, b: 2}, {c: 3}, 4]: the vector, two maps, three keys, three
+    // values and 4.
+    let a = Symbol::try_from_small_str("a")?.to_val();
+    let b = Symbol::try_from_small_str("b")?.to_val();
+    let c = Symbol::try_from_small_str("c")?.to_val();
+    let m1 = host.map_put(host.map_new()?, a, u(1))?;
+    let m1 = host.map_put(m1, b, u(2))?;
+    let m2 = host.map_put(host.map_new()?, c, u(3))?;
+    let v = host.vec_new_from_slice(&[m1.to_val(), m2.to_val(), u(4)])?;
+    assert_eq!(host.object_shape(v.to_val())?, (2, 10));
+
+    // Nesting up to the depth limit is fine, one more level is not.
+    let mut nested = host.vec_new()?;
+    for _ in 1..DEFAULT_HOST_DEPTH_LIMIT {
+        nested = host.vec_new_from_slice(&[nested.to_val()])?;
+    }
+    assert_eq!(
+        host.object_shape(nested.to_val())?,
+        (DEFAULT_HOST_DEPTH_LIMIT, DEFAULT_HOST_DEPTH_LIMIT)
+    );
+    let too_deep = host.vec_new_from_slice(&[nested.to_val()])?;
+    assert!(HostError::result_matches_err(
+        host.object_shape(too_deep.to_val()),
+        (ScErrorType::Context, ScErrorCode::ExceededLimit)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn contracts_read_config_entries() -> Result<(), HostError> {
+    use crate::{testutils::wasm, EnvBase, Symbol, TryFromVal};
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(&wasm::wasm_module_calling_get_config());
+    let get = |key: &str| -> Result<Val, HostError> {
+        let args = host.vec_new_from_slice(&[Symbol::try_from_val(&host, &key)?.to_val()])?;
+        host.call(contract, Symbol::try_from_small_str("get")?, args)
+    };
+
+    let limit = Symbol::try_from_small_str("limit")?;
+    host.set_config_entry(limit, 100_u32.into())?;
+    let flag = Symbol::try_from_val(&host, &"a_rather_long_feature_flag")?;
+    let bytes = host.bytes_new_from_slice(b"on")?;
+    host.set_config_entry(flag, bytes.to_val())?;
+
+    assert_eq!(u32::try_from(get("limit")?)?, 100);
+    let res = get("a_rather_long_feature_flag")?;
+    assert_eq!(host.obj_cmp(res, bytes.to_val())?, 0);
+    assert!(get("unset")?.is_void());
+
+    // Setting an entry again replaces it.
+    host.set_config_entry(limit, 200_u32.into())?;
+    assert_eq!(u32::try_from(get("limit")?)?, 200);
+    Ok(())
+}
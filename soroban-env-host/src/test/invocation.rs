@@ -489,3 +489,114 @@ fn guest_error() -> Result<(), HostError> {
     );
     Ok(())
 }
+
+#[cfg(feature = "next")]
+#[test]
+fn get_call_stack_in_nested_calls() -> Result<(), HostError> {
+    use crate::testutils::wasm::wasm_module_returning_call_stack;
+
+    let host = Host::test_host_with_recording_footprint();
+    let a = host.register_test_contract_wasm(&wasm_module_returning_call_stack(2));
+    let b = host.register_test_contract_wasm(&wasm_module_returning_call_stack(1));
+    let c = host.register_test_contract_wasm(&wasm_module_returning_call_stack(0));
+
+    // Re-entering a contract that is already on the stack is prohibited, so
+    // use a chain of distinct contracts A -> B -> C.
+    let args = host.vec_new_from_slice(&[b.to_val(), c.to_val()])?;
+    let res = host.call(a, Symbol::try_from_small_str("stack")?, args)?;
+    let expected = host.vec_new_from_slice(&[a.to_val(), b.to_val(), c.to_val()])?;
+    assert_eq!(host.obj_cmp(res, expected.to_val())?, 0);
+
+    // Outside of any contract the stack is empty.
+    let stack = host.get_call_stack()?;
+    assert_eq!(u32::from(host.vec_len(stack)?), 0);
+    Ok(())
+}
+
+#[test]
+fn contract_exports_checks_function_names() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(ADD_I32);
+    let id = host.contract_id_from_address(contract)?;
+    assert!(host.contract_exports(&id, &Symbol::try_from_small_str("add")?)?);
+    assert!(!host.contract_exports(&id, &Symbol::try_from_small_str("sub")?)?);
+    Ok(())
+}
+
+struct CountCalls(std::cell::Cell<u32>);
+
+impl ContractFunctionSet for CountCalls {
+    fn call(&self, _func: &Symbol, _host: &Host, _args: &[Val]) -> Option<Val> {
+        self.0.set(self.0.get() + 1);
+        Some(().into())
+    }
+}
+
+#[test]
+fn malformed_call_args_rejected_before_contract() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let addr = host.add_host_object(xdr::ScAddress::Contract(xdr::Hash([1; 32])))?;
+    let contract = Rc::new(CountCalls(Default::default()));
+    host.register_test_contract(addr, contract.clone())?;
+    let sym = Symbol::try_from_small_str("go")?;
+    // Registering may run a constructor; only count the calls below.
+    let calls_before = contract.0.get();
+
+    let args = host.vec_new_from_slice(&[1u32.into()])?;
+    host.call(addr, sym, args)?;
+    assert_eq!(contract.0.get(), calls_before + 1);
+
+    // A `True` with a nonzero body, and a reserved tag: neither is a value
+    // the host ever produces.
+    for bad in [
+        Val::from_payload((1 << 8) | Tag::True as u64),
+        Val::from_payload(Tag::Bad as u64),
+    ] {
+        assert!(!bad.is_good());
+        let args = host.vec_new_from_slice(&[1u32.into(), bad])?;
+        let res = host.call(addr, sym, args);
+        assert!(HostError::result_matches_err(
+            res,
+            (ScErrorType::Value, ScErrorCode::InvalidInput)
+        ));
+    }
+    assert_eq!(contract.0.get(), calls_before + 1);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn get_current_contract_code_hash_matches_wasm() -> Result<(), HostError> {
+    use crate::testutils::wasm::wasm_module_returning_code_hash;
+
+    let host = Host::test_host_with_recording_footprint();
+    let wasm = wasm_module_returning_code_hash();
+    let contract = host.register_test_contract_wasm(&wasm);
+    let args = host.vec_new()?;
+    let res = host.call(contract, Symbol::try_from_small_str("code_hash")?, args)?;
+    let expected = host.compute_hash_sha256(host.bytes_new_from_slice(&wasm)?)?;
+    assert_eq!(host.obj_cmp(res, expected.to_val())?, 0);
+
+    // Outside of any contract there is no code to hash.
+    assert!(HostError::result_matches_err(
+        host.get_current_contract_code_hash(),
+        (ScErrorType::Context, ScErrorCode::InvalidAction)
+    ));
+    Ok(())
+}
+
+#[test]
+fn contract_function_arity_reads_export_type() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(ADD_I32);
+    let id = host.contract_id_from_address(contract)?;
+    assert_eq!(
+        host.contract_function_arity(&id, &Symbol::try_from_small_str("add")?)?,
+        2
+    );
+    assert!(HostError::result_matches_err(
+        host.contract_function_arity(&id, &Symbol::try_from_small_str("sub")?),
+        (ScErrorType::WasmVm, ScErrorCode::MissingValue)
+    ));
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use crate::xdr::{AccountId, Hash, PublicKey, ScAddress, ScErrorCode, ScErrorType, Uint256};
+use crate::{Env, EnvBase, Error, Host, HostError, Symbol, TryIntoVal, Val};
+
+// Builds `[{1: 0x00ff, a: -5_i64}, {b: [true, ()]}, "say \"hi\""]`.
+fn nested_value(host: &Host) -> Result<Val, HostError> {
+    let a = Symbol::try_from_small_str("a")?.to_val();
+    let b = Symbol::try_from_small_str("b")?.to_val();
+    let i: Val = (-5_i64).try_into_val(host)?;
+    let bytes = host.bytes_new_from_slice(&[0x00, 0xff])?;
+    let m1 = host.map_put(host.map_new()?, a, i)?;
+    let m1 = host.map_put(m1, 1_u32.into(), bytes.to_val())?;
+    let v = host.vec_new_from_slice(&[true.into(), Val::VOID.to_val()])?;
+    let m2 = host.map_put(host.map_new()?, b, v.to_val())?;
+    let s = host.string_new_from_slice(b"say \"hi\"")?;
+    Ok(host
+        .vec_new_from_slice(&[m1.to_val(), m2.to_val(), s.to_val()])?
+        .to_val())
+}
+
+#[test]
+fn to_json_renders_nested_values() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let v = nested_value(&host)?;
+    assert_eq!(
+        host.to_json(v)?,
+        r#"[{"1":"bytes:00ff","sym:a":"i64:-5"},{"sym:b":[true,null]},"str:say \"hi\""]"#
+    );
+
+    // Equal values built separately render identically.
+    let w = nested_value(&host)?;
+    assert_ne!(v.get_payload(), w.get_payload());
+    assert_eq!(host.to_json(w)?, host.to_json(v)?);
+
+    // Scalars.
+    assert_eq!(host.to_json(7_u32.into())?, "7");
+    assert_eq!(host.to_json((-7_i32).into())?, r#""i32:-7""#);
+    let big: Val = u128::MAX.try_into_val(&host)?;
+    assert_eq!(
+        host.to_json(big)?,
+        r#""u128:340282366920938463463374607431768211455""#
+    );
+
+    // Errors have no rendering.
+    assert!(HostError::result_matches_err(
+        host.to_json(Error::from_contract_error(1).to_val()),
+        (ScErrorType::Value, ScErrorCode::UnexpectedType)
+    ));
+    Ok(())
+}
+
+#[test]
+fn from_json_parses_canonical_json() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let text = r#"[{"1":"bytes:00ff","sym:a":"i64:-5"},{"sym:b":[true,null]},"str:say \"hi\""]"#;
+    let v = host.from_json(text)?;
+    assert_eq!(host.obj_cmp(v, nested_value(&host)?)?, 0);
+    assert_eq!(host.to_json(v)?, text);
+
+    // Whitespace and entry order don't matter.
+    let v = host.from_json(
+        r#" [ { "sym:a" : "i64:-5", "1" : "bytes:00ff" }, {"sym:b": [ true, null ]}, "str:say \"hi\"" ] "#,
+    )?;
+    assert_eq!(host.obj_cmp(v, nested_value(&host)?)?, 0);
+
+    // Every kind of scalar, and non-string keys, round-trip.
+    for text in [
+        "null",
+        "4294967295",
+        r#""i32:-2147483648""#,
+        r#""u64:18446744073709551615""#,
+        r#""timepoint:1234""#,
+        r#""duration:0""#,
+        r#""i128:-170141183460469231731687303715884105728""#,
+        r#""u256:115792089237316195423570985008687907853269984665640564039457584007913129639935""#,
+        r#""i256:-1""#,
+        r#""sym:a_rather_long_symbol""#,
+        r#""str:line\nbreak \u0001 and ünïcode""#,
+        r#""bytes:""#,
+        r#"{"null":1,"[1,2]":[],"{\"sym:k\":2}":{}}"#,
+    ] {
+        assert_eq!(host.to_json(host.from_json(text)?)?, text);
+    }
+    let account = ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([1; 32]))));
+    for (addr, strkey_prefix) in [(account, "G"), (ScAddress::Contract(Hash([2; 32])), "C")] {
+        let addr = host.add_host_object(addr)?.to_val();
+        let text = host.to_json(addr)?;
+        assert!(text.starts_with(&format!("\"address:{strkey_prefix}")));
+        assert_eq!(host.obj_cmp(host.from_json(&text)?, addr)?, 0);
+    }
+
+    // Ambiguous or unsupported constructs are rejected.
+    for text in [
+        "-1",
+        "1.5",
+        "1e3",
+        "01",
+        "4294967296",
+        r#""i64:+5""#,
+        r#""i64:05""#,
+        r#""no_prefix""#,
+        r#""bytes:0F""#,
+        r#""bytes:abc""#,
+        r#"{"sym:a":1,"sym:a":2}"#,
+        r#"{"\"sym:a\"":1}"#,
+        r#"{" 1":2}"#,
+        r#"{"[1, 2]":[]}"#,
+        "[1,]",
+        "[1] 2",
+        r#""str:unterminated"#,
+    ] {
+        assert!(
+            HostError::result_matches_err(
+                host.from_json(text),
+                (ScErrorType::Value, ScErrorCode::InvalidInput)
+            ),
+            "{text}"
+        );
+    }
+    assert!(host.from_json(r#""sym:not a symbol""#).is_err());
+    Ok(())
+}
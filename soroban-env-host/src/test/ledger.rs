@@ -1,4 +1,4 @@
-use soroban_env_common::{xdr::ScBytes, Env};
+use soroban_env_common::{xdr::ScBytes, Env, TryIntoVal};
 
 use crate::{
     budget::Budget,
@@ -22,3 +22,38 @@ fn ledger_network_id() -> Result<(), HostError> {
     assert_eq!(np, vec![7; 32],);
     Ok(())
 }
+
+#[test]
+fn ledger_info_drives_ledger_host_functions() -> Result<(), HostError> {
+    use crate::{
+        xdr::{ScErrorCode, ScErrorType},
+        LedgerInfo,
+    };
+
+    // A fresh host has no ledger info at all, rather than a zeroed one, so
+    // reading it is an error until the embedder supplies it.
+    let host = Host::default();
+    assert!(HostError::result_matches_err(
+        host.get_ledger_timestamp(),
+        (ScErrorType::Context, ScErrorCode::InternalError)
+    ));
+
+    host.set_ledger_info(LedgerInfo {
+        protocol_version: Host::current_test_protocol(),
+        sequence_number: 1234,
+        timestamp: 1_700_000_000,
+        network_id: [3; 32],
+        ..Default::default()
+    })?;
+    assert_eq!(u32::from(host.get_ledger_sequence()?), 1234);
+    let timestamp: u64 = host.get_ledger_timestamp()?.try_into_val(&host)?;
+    assert_eq!(timestamp, 1_700_000_000);
+    let obj = host.get_ledger_network_id()?;
+    let np = host.visit_obj(obj, |np: &ScBytes| Ok(np.to_vec()))?;
+    assert_eq!(np, vec![3; 32]);
+
+    host.with_mut_ledger_info(|li| li.timestamp += 5)?;
+    let timestamp: u64 = host.get_ledger_timestamp()?.try_into_val(&host)?;
+    assert_eq!(timestamp, 1_700_000_005);
+    Ok(())
+}
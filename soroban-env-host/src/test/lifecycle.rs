@@ -341,6 +341,66 @@ fn test_create_contract_from_source_account() {
     create_contract_from_source_account(&test_host(), &ADD_I32);
 }
 
+#[test]
+fn test_create_two_contracts_from_same_wasm_hash() {
+    let host = test_host();
+    let source_account = generate_account_id(&host);
+    let create = |salt: [u8; 32]| {
+        create_contract_with_constructor(
+            &host,
+            source_account.clone(),
+            salt,
+            ADD_I32,
+            &vec![],
+            Default::default(),
+        )
+        .unwrap()
+    };
+    let id0 = create([0; 32]);
+    let id1 = create([1; 32]);
+
+    // The salt goes into the contract id, so the instances are distinct, but
+    // both refer to the single uploaded copy of the code.
+    assert_ne!(id0, id1);
+    let wasm_hash = get_contract_wasm_ref(&host, id0);
+    assert_eq!(wasm_hash, get_contract_wasm_ref(&host, id1));
+    assert_eq!(ADD_I32, get_contract_wasm(&host, wasm_hash));
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn test_contracts_share_code() -> Result<(), HostError> {
+    use crate::{testutils::wasm::wasm_module_calling_contracts_share_code, AddressObject};
+    use soroban_test_wasms::CONTRACT_STORAGE;
+    let host = Host::test_host_with_recording_footprint();
+    let checker = host.register_test_contract_wasm(&wasm_module_calling_contracts_share_code());
+    let share = |a: AddressObject, b: AddressObject| -> Result<bool, HostError> {
+        let args = host.vec_new_from_slice(&[a.to_val(), b.to_val()])?;
+        let res = host.call(checker, Symbol::try_from_small_str("share")?, args)?;
+        Ok(res.try_into_val(&host)?)
+    };
+
+    // The same code installed under two ids is shared, whatever their salts.
+    let a0 = host.register_test_contract_wasm(ADD_I32);
+    let a1 = host.register_test_contract_wasm(ADD_I32);
+    assert_ne!(
+        host.contract_id_from_address(a0)?,
+        host.contract_id_from_address(a1)?
+    );
+    assert!(share(a0, a1)?);
+    assert!(share(a0, a0)?);
+
+    // Different code isn't.
+    let b0 = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    assert!(!share(a0, b0)?);
+    assert!(!share(b0, checker)?);
+
+    // Both contracts have to exist.
+    let missing = host.add_host_object(ScAddress::Contract(Hash([0xab; 32])))?;
+    assert!(share(a0, missing).is_err());
+    Ok(())
+}
+
 pub(crate) fn sha256_hash_id_preimage<T: xdr::WriteXdr>(pre_image: T) -> xdr::Hash {
     let mut buf = Limited::new(Vec::new(), DEFAULT_XDR_RW_LIMITS);
     pre_image
@@ -4,8 +4,66 @@ use crate::{
     xdr::{ScBytes, ScErrorCode, ScErrorType, ScString, ScSymbol},
     Env, Host, HostError, Symbol, SymbolSmall, U32Val, Val,
 };
+#[cfg(feature = "next")]
+use crate::{
+    testutils::wasm::wasm_module_with_serialize_to_linear_memory, BytesObject, EnvBase, TryFromVal,
+};
 use soroban_synth_wasm::{Arity, LocalRef, ModEmitter, Operand};
 
 use soroban_env_macros::generate_linear_memory_host_fn_tests;
 
 generate_linear_memory_host_fn_tests!("../soroban-env-common/env.json");
+
+#[cfg(feature = "next")]
+#[test]
+fn serialize_to_linear_memory_round_trips() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id_obj = host
+        .register_test_contract_wasm(wasm_module_with_serialize_to_linear_memory(256).as_slice());
+    let v = host.test_vec_val(&[1u32, 2, 3])?;
+    let args = host.vec_new_from_slice(&[v])?;
+    let res = host.call(contract_id_obj, Symbol::try_from_small_str("test")?, args)?;
+    let from_guest = BytesObject::try_from_val(&host, &res)?;
+    let expected = host.serialize_to_bytes(v)?;
+    assert_eq!(host.obj_cmp(from_guest.into(), expected.into())?, 0);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn serialize_to_linear_memory_buffer_too_small() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id_obj =
+        host.register_test_contract_wasm(wasm_module_with_serialize_to_linear_memory(4).as_slice());
+    let v = host.test_vec_val(&[1u32, 2, 3])?;
+    let args = host.vec_new_from_slice(&[v])?;
+    let res = host.call(contract_id_obj, Symbol::try_from_small_str("test")?, args);
+    assert!(HostError::result_matches_err(
+        res,
+        (ScErrorType::WasmVm, ScErrorCode::IndexBounds)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn serialize_to_linear_memory_pos_oob() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id_obj = host
+        .register_test_contract_wasm(wasm_module_calling_serialize_to_linear_memory().as_slice());
+    let args = host.vec_new_from_slice(&[
+        U32Val::from(7).to_val(),
+        U32Val::from(0x10_000).to_val(),
+        U32Val::from(0x100).to_val(),
+    ])?;
+    let res = host.call(
+        contract_id_obj,
+        Symbol::try_from_small_str("loadmem3")?,
+        args,
+    );
+    assert!(HostError::result_matches_err(
+        res,
+        (ScErrorType::WasmVm, ScErrorCode::IndexBounds)
+    ));
+    Ok(())
+}
@@ -1,11 +1,13 @@
+#[cfg(feature = "next")]
+use crate::VecObject;
 use crate::{
     testutils::wasm,
     xdr::{
         AccountId, ContractCostType, LedgerEntry, LedgerKey, LedgerKeyAccount, PublicKey,
         ScErrorCode, ScErrorType, ScMap, ScMapEntry, ScVal, ScVec, Uint256, VecM,
     },
-    Env, Error, ErrorHandler, Host, HostError, MapObject, MeteredOrdMap, Symbol, SymbolSmall,
-    TryFromVal, U32Val, Val,
+    Env, EnvBase, Error, ErrorHandler, Host, HostError, MapObject, MeteredOrdMap, Symbol,
+    SymbolSmall, TryFromVal, U32Val, Val,
 };
 use more_asserts::assert_ge;
 use soroban_test_wasms::LINEAR_MEMORY;
@@ -351,7 +353,6 @@ fn scmap_invalid_element() {
 
 #[test]
 fn map_build_bad_element_integrity() -> Result<(), HostError> {
-    use crate::EnvBase;
     let host = observe_host!(Host::test_host());
     let obj = host.map_new()?;
 
@@ -419,7 +420,6 @@ fn large_map_exceeds_budget() {
 
 #[test]
 fn initialization_invalid() -> Result<(), HostError> {
-    use crate::EnvBase;
     let host = observe_host!(Host::test_host());
 
     // Out of order keys
@@ -616,3 +616,393 @@ fn linear_memory_operations() -> Result<(), HostError> {
     }
     Ok(())
 }
+
+#[cfg(feature = "next")]
+#[test]
+fn map_keys_in_range() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let mut m = host.map_new()?;
+    for i in 1..=5u32 {
+        m = host.map_put(m, i.into(), (i * 10).into())?;
+    }
+
+    // A range selecting a middle subset: [2, 4) -> [2, 3].
+    let keys = host.map_keys_in_range(m, 2u32.into(), 4u32.into())?;
+    let expected = host.test_vec_obj::<u32>(&[2, 3])?;
+    assert_eq!(host.obj_cmp(keys.into(), expected.into())?, 0);
+
+    // Bounds that aren't keys in the map behave the same way.
+    let keys = host.map_keys_in_range(m, 0u32.into(), 3u32.into())?;
+    let expected = host.test_vec_obj::<u32>(&[1, 2])?;
+    assert_eq!(host.obj_cmp(keys.into(), expected.into())?, 0);
+
+    // Empty ranges, both with lo == hi and lo > hi.
+    let keys = host.map_keys_in_range(m, 3u32.into(), 3u32.into())?;
+    assert_eq!(u32::from(host.vec_len(keys)?), 0);
+    let keys = host.map_keys_in_range(m, 4u32.into(), 2u32.into())?;
+    assert_eq!(u32::from(host.vec_len(keys)?), 0);
+
+    // A range covering everything.
+    let keys = host.map_keys_in_range(m, 0u32.into(), 100u32.into())?;
+    assert_eq!(host.obj_cmp(keys.into(), host.map_keys(m)?.into())?, 0);
+    Ok(())
+}
+
+#[test]
+fn map_update_counter() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let k = Symbol::try_from_small_str("count")?.to_val();
+    let incr = |v: Option<Val>| -> Result<Val, HostError> {
+        let n = match v {
+            Some(v) => u32::from(U32Val::try_from(v)?),
+            None => 0,
+        };
+        Ok(U32Val::from(n + 1).to_val())
+    };
+
+    let m = host.map_new()?;
+    let m = host.map_update(m, k, incr)?;
+    let expected = host.map_new_from_slices(&["count"], &[U32Val::from(1).to_val()])?;
+    assert_eq!(host.obj_cmp(m.into(), expected.into())?, 0);
+
+    let m = host.map_update(m, k, incr)?;
+    let expected = host.map_new_from_slices(&["count"], &[U32Val::from(2).to_val()])?;
+    assert_eq!(host.obj_cmp(m.into(), expected.into())?, 0);
+
+    // Other keys are left alone.
+    let other = host.map_put(m, U32Val::from(7).to_val(), U32Val::from(7).to_val())?;
+    let updated = host.map_update(other, k, incr)?;
+    assert_eq!(
+        host.map_get(updated, U32Val::from(7).to_val())?
+            .get_payload(),
+        U32Val::from(7).to_val().get_payload()
+    );
+    assert_eq!(u32::from(host.map_len(updated)?), 2);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn map_diff_keys() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let u = |n: u32| U32Val::from(n).to_val();
+
+    let a = host.map_new_from_slices(&["a", "b", "c"], &[u(1), u(2), u(3)])?;
+    let same = host.map_new_from_slices(&["a", "b", "c"], &[u(1), u(2), u(3)])?;
+    let diff = host.map_diff_keys(a, same)?;
+    assert_eq!(u32::from(host.vec_len(diff)?), 0);
+
+    let changed = host.map_new_from_slices(&["a", "b", "c"], &[u(1), u(5), u(3)])?;
+    let diff = host.map_diff_keys(a, changed)?;
+    let expected = host.vec_new_from_slice(&[Symbol::try_from_small_str("b")?.to_val()])?;
+    assert_eq!(host.obj_cmp(diff.into(), expected.into())?, 0);
+
+    let disjoint = host.map_new_from_slices(&["d", "e"], &[u(4), u(5)])?;
+    let diff = host.map_diff_keys(disjoint, a)?;
+    let mut keys = vec![];
+    for k in ["a", "b", "c", "d", "e"] {
+        keys.push(Symbol::try_from_small_str(k)?.to_val());
+    }
+    let expected = host.vec_new_from_slice(&keys)?;
+    assert_eq!(host.obj_cmp(diff.into(), expected.into())?, 0);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn map_fold_by_contract_function() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(&wasm::wasm_module_with_map_fold());
+    let fold = |m: MapObject, func: &str| -> Result<Val, HostError> {
+        let args = host.vec_new_from_slice(&[
+            m.to_val(),
+            U32Val::from(0).to_val(),
+            Symbol::try_from_small_str(func)?.to_val(),
+        ])?;
+        host.call(contract, Symbol::try_from_small_str("fold")?, args)
+    };
+
+    let m = host.map_new()?;
+    let m = host.map_put(m, U32Val::from(1).to_val(), U32Val::from(10).to_val())?;
+    let m = host.map_put(m, U32Val::from(2).to_val(), U32Val::from(20).to_val())?;
+    let res = U32Val::try_from(fold(m, "add_val")?)?;
+    assert_eq!(u32::from(res), 30);
+
+    // An empty map folds to the initial value.
+    let res = U32Val::try_from(fold(host.map_new()?, "add_val")?)?;
+    assert_eq!(u32::from(res), 0);
+
+    // An error from the function stops the fold.
+    assert!(fold(m, "missing").is_err());
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn map_filter_values_by_contract_predicate() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(&wasm::wasm_module_with_map_filter_values());
+    let u = |x: u32| U32Val::from(x).to_val();
+    let filter = |m: MapObject, func: &str| -> Result<Val, HostError> {
+        let args =
+            host.vec_new_from_slice(&[m.to_val(), Symbol::try_from_small_str(func)?.to_val()])?;
+        host.call(contract, Symbol::try_from_small_str("filter")?, args)
+    };
+
+    let m = host.map_new()?;
+    let m = host.map_put(m, u(1), u(10))?;
+    let m = host.map_put(m, u(2), u(5))?;
+    let m = host.map_put(m, u(3), u(20))?;
+    let res = filter(m, "greater_than_8")?;
+    let expected = host.map_new()?;
+    let expected = host.map_put(expected, u(1), u(10))?;
+    let expected = host.map_put(expected, u(3), u(20))?;
+    assert_eq!(host.obj_cmp(res, expected.to_val())?, 0);
+
+    // An empty map filters to an empty map.
+    let res = MapObject::try_from(filter(host.map_new()?, "greater_than_8")?)?;
+    assert_eq!(u32::from(host.map_len(res)?), 0);
+
+    // A predicate that doesn't return a `Bool` is an error.
+    assert!(filter(m, "filter").is_err());
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn map_merge_with_contract_combiner() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(&wasm::wasm_module_with_map_merge_with());
+    let u = |x: u32| U32Val::from(x).to_val();
+    let merge = |a: MapObject, b: MapObject, func: &str| -> Result<Val, HostError> {
+        let args = host.vec_new_from_slice(&[
+            a.to_val(),
+            b.to_val(),
+            Symbol::try_from_small_str(func)?.to_val(),
+        ])?;
+        host.call(contract, Symbol::try_from_small_str("merge")?, args)
+    };
+
+    let a = host.map_put(host.map_new()?, u(1), u(10))?;
+    let b = host.map_new()?;
+    let b = host.map_put(b, u(1), u(5))?;
+    let b = host.map_put(b, u(2), u(7))?;
+    let res = merge(a, b, "add_val")?;
+    let expected = host.map_new()?;
+    let expected = host.map_put(expected, u(1), u(15))?;
+    let expected = host.map_put(expected, u(2), u(7))?;
+    assert_eq!(host.obj_cmp(res, expected.to_val())?, 0);
+
+    // Keys unique to either side are copied without calling the combiner.
+    let c = host.map_put(host.map_new()?, u(0), u(3))?;
+    let res = merge(c, b, "missing")?;
+    let expected = host.map_put(b, u(0), u(3))?;
+    assert_eq!(host.obj_cmp(res, expected.to_val())?, 0);
+    let res = merge(host.map_new()?, host.map_new()?, "missing")?;
+    assert_eq!(u32::from(host.map_len(MapObject::try_from(res)?)?), 0);
+
+    // An error from the combiner fails the merge.
+    assert!(merge(a, b, "missing").is_err());
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn map_canonicalize() -> Result<(), HostError> {
+    use crate::host_object::HostMap;
+    let host = Host::test_host();
+    let u = |x: u32| U32Val::from(x).to_val();
+    let sym = |s: &str| -> Result<Val, HostError> { Ok(Symbol::try_from_small_str(s)?.to_val()) };
+
+    // A map built through the host is already canonical and comes back as
+    // an equal copy.
+    let m = host.map_new()?;
+    let m = host.map_put(m, u(2), sym("b")?)?;
+    let m = host.map_put(m, u(1), sym("a")?)?;
+    let m = host.map_put(m, sym("k")?, u(3))?;
+    let copy = host.map_canonicalize(m)?;
+    assert_ne!(copy.to_val().get_payload(), m.to_val().get_payload());
+    assert_eq!(host.obj_cmp(copy.to_val(), m.to_val())?, 0);
+
+    // Out-of-order entries are sorted and later duplicates win.
+    let bytes = |b: &[u8]| -> Result<Val, HostError> { Ok(host.bytes_new_from_slice(b)?.to_val()) };
+    let canonical = host.canonical_map_from_entries(vec![
+        (sym("k")?, u(0)),
+        (u(2), sym("b")?),
+        (bytes(&[1])?, u(4)),
+        (u(1), sym("x")?),
+        (sym("k")?, u(3)),
+        (bytes(&[1])?, u(5)),
+        (u(1), sym("a")?),
+    ])?;
+    let canonical = host.add_host_object::<HostMap>(canonical)?;
+    let expected = host.map_put(m, bytes(&[1])?, u(5))?;
+    assert_eq!(host.obj_cmp(canonical.to_val(), expected.to_val())?, 0);
+    assert_eq!(u32::from(host.map_len(canonical)?), 4);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn map_sorted_vec_round_trip() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let u = |x: u32| U32Val::from(x).to_val();
+    let pair = |k: u32, v: u32| -> Result<Val, HostError> {
+        Ok(host.vec_new_from_slice(&[u(k), u(v)])?.to_val())
+    };
+
+    let m = host.map_new()?;
+    let m = host.map_put(m, u(2), u(20))?;
+    let m = host.map_put(m, u(1), u(10))?;
+    let m = host.map_put(m, u(3), u(30))?;
+    let v = host.map_to_sorted_vec(m)?;
+    let expected = host.vec_new_from_slice(&[pair(1, 10)?, pair(2, 20)?, pair(3, 30)?])?;
+    assert_eq!(host.obj_cmp(v.to_val(), expected.to_val())?, 0);
+    let back = host.map_from_sorted_vec(v)?;
+    assert_eq!(host.obj_cmp(back.to_val(), m.to_val())?, 0);
+
+    // The empty map round-trips too.
+    let empty = host.map_to_sorted_vec(host.map_new()?)?;
+    assert_eq!(u32::from(host.vec_len(empty)?), 0);
+    assert_eq!(
+        u32::from(host.map_len(host.map_from_sorted_vec(empty)?)?),
+        0
+    );
+
+    // Unsorted or repeated keys are rejected.
+    let code = (ScErrorType::Object, ScErrorCode::InvalidInput);
+    let unsorted = host.vec_new_from_slice(&[pair(2, 20)?, pair(1, 10)?])?;
+    assert!(HostError::result_matches_err(
+        host.map_from_sorted_vec(unsorted),
+        code
+    ));
+    let repeated = host.vec_new_from_slice(&[pair(1, 10)?, pair(1, 11)?])?;
+    assert!(HostError::result_matches_err(
+        host.map_from_sorted_vec(repeated),
+        code
+    ));
+
+    // Entries must be 2-element vectors.
+    let short = host.vec_new_from_slice(&[host.vec_new_from_slice(&[u(1)])?.to_val()])?;
+    assert!(HostError::result_matches_err(
+        host.map_from_sorted_vec(short),
+        (ScErrorType::Object, ScErrorCode::UnexpectedSize)
+    ));
+    let not_vec = host.vec_new_from_slice(&[u(1)])?;
+    assert!(HostError::result_matches_err(
+        host.map_from_sorted_vec(not_vec),
+        (ScErrorType::Value, ScErrorCode::UnexpectedType)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn map_slice_by_rank() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let u = |x: u32| U32Val::from(x).to_val();
+    let map_of = |keys: &[u32]| -> Result<MapObject, HostError> {
+        let mut m = host.map_new()?;
+        for k in keys {
+            m = host.map_put(m, u(*k), u(k * 10))?;
+        }
+        Ok(m)
+    };
+    let m = map_of(&[50, 10, 40, 20, 30])?;
+    let slice = |start: u32, end: u32| host.map_slice_by_rank(m, start.into(), end.into());
+
+    // Ranks follow key order, not insertion order.
+    let page = slice(1, 4)?;
+    assert_eq!(
+        host.obj_cmp(page.to_val(), map_of(&[20, 30, 40])?.to_val())?,
+        0
+    );
+    let all = slice(0, 5)?;
+    assert_eq!(host.obj_cmp(all.to_val(), m.to_val())?, 0);
+
+    // An empty range gives an empty map.
+    assert_eq!(u32::from(host.map_len(slice(2, 2)?)?), 0);
+    assert_eq!(u32::from(host.map_len(slice(5, 5)?)?), 0);
+
+    // Bounds are checked as in `vec_slice`.
+    let oob = (ScErrorType::Object, ScErrorCode::IndexBounds);
+    assert!(HostError::result_matches_err(slice(0, 6), oob));
+    assert!(HostError::result_matches_err(slice(6, 6), oob));
+    assert!(HostError::result_matches_err(
+        slice(3, 2),
+        (ScErrorType::Object, ScErrorCode::InvalidInput)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn map_to_key_val_vecs() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let u = |x: u32| U32Val::from(x).to_val();
+    let mut m = host.map_new()?;
+    for k in [3, 1, 2] {
+        m = host.map_put(m, u(k), u(k * 10))?;
+    }
+
+    let res = host.map_to_key_val_vecs(m)?;
+    assert_eq!(u32::from(host.vec_len(res)?), 2);
+    let keys = host.vec_get(res, U32Val::from(0))?;
+    let vals = host.vec_get(res, U32Val::from(1))?;
+    assert_eq!(host.obj_cmp(keys, host.map_keys(m)?.to_val())?, 0);
+    assert_eq!(host.obj_cmp(vals, host.map_values(m)?.to_val())?, 0);
+    assert_eq!(
+        host.obj_cmp(keys, host.test_vec_val::<u32>(&[1, 2, 3])?)?,
+        0
+    );
+    assert_eq!(
+        host.obj_cmp(vals, host.test_vec_val::<u32>(&[10, 20, 30])?)?,
+        0
+    );
+
+    let res = host.map_to_key_val_vecs(host.map_new()?)?;
+    let empty = host.test_vec_val::<u32>(&[])?;
+    assert_eq!(host.obj_cmp(host.vec_get(res, U32Val::from(0))?, empty)?, 0);
+    assert_eq!(host.obj_cmp(host.vec_get(res, U32Val::from(1))?, empty)?, 0);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn map_pop_min_and_max() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let u = |x: u32| U32Val::from(x).to_val();
+    let m = host.map_new()?;
+    let m = host.map_put(m, u(2), u(20))?;
+    let m = host.map_put(m, u(1), u(10))?;
+    let m = host.map_put(m, u(3), u(30))?;
+    let split = |res: VecObject| -> Result<(MapObject, Val), HostError> {
+        assert_eq!(u32::from(host.vec_len(res)?), 2);
+        let m = MapObject::try_from(host.vec_get(res, 0_u32.into())?)?;
+        Ok((m, host.vec_get(res, 1_u32.into())?))
+    };
+
+    let (rest, popped) = split(host.map_pop_min(m)?)?;
+    assert_eq!(host.obj_cmp(popped, host.test_vec_val(&[1_u32, 10])?)?, 0);
+    let expected = host.map_put(host.map_del(m, u(1))?, u(2), u(20))?;
+    assert_eq!(host.obj_cmp(rest.to_val(), expected.to_val())?, 0);
+
+    let (rest, popped) = split(host.map_pop_max(m)?)?;
+    assert_eq!(host.obj_cmp(popped, host.test_vec_val(&[3_u32, 30])?)?, 0);
+    assert_eq!(
+        host.obj_cmp(rest.to_val(), host.map_del(m, u(3))?.to_val())?,
+        0
+    );
+
+    // The original map is unchanged.
+    assert_eq!(u32::from(host.map_len(m)?), 3);
+
+    // Popping an empty map gives back the map and void.
+    let empty = host.map_new()?;
+    for res in [host.map_pop_min(empty)?, host.map_pop_max(empty)?] {
+        let (rest, popped) = split(res)?;
+        assert_eq!(rest.to_val().get_payload(), empty.to_val().get_payload());
+        assert!(popped.is_void());
+    }
+    Ok(())
+}
@@ -437,3 +437,342 @@ fn test_u256_bytes_roundtrip() -> Result<(), HostError> {
     assert_eq!(num, num_back);
     Ok(())
 }
+
+#[test]
+fn test_i256_host_wrapper_arith() -> Result<(), HostError> {
+    use crate::HostI256;
+
+    let host = Host::test_host();
+    let a = HostI256::try_from_val(&host, &I256::new(7))?;
+    let b = HostI256::try_from_val(&host, &(I256::MAX - 2))?;
+
+    // Operators delegate to the raw host functions.
+    let sum = (&a + &b)?;
+    let raw_sum = host.i256_add(a.as_val(), b.as_val())?;
+    assert_eq!(host.obj_cmp(sum.as_val().to_val(), raw_sum.to_val())?, 0);
+    let diff = (&b - &a)?;
+    assert_eq!(diff.to_i256()?, I256::MAX - 9);
+    let prod = (&a * &a)?;
+    assert_eq!(prod.to_i256()?, I256::new(49));
+
+    // Ordering agrees with obj_cmp on the underlying values.
+    assert_eq!(a.compare(&b)?, Ordering::Less);
+    assert_eq!(host.obj_cmp(a.as_val().to_val(), b.as_val().to_val())?, -1);
+    assert_eq!(host.compare(&b, &b)?, Ordering::Equal);
+    assert!(a < b && b > a && a <= a.clone());
+    assert!(a == a.clone() && a != b);
+
+    // Failures surface as errors rather than panics.
+    let zero = HostI256::try_from_val(&host, &I256::ZERO)?;
+    let code = (ScErrorType::Object, ScErrorCode::ArithDomain);
+    assert!(HostError::result_matches_err(&a / &zero, code));
+    let three = HostI256::try_from_val(&host, &I256::new(3))?;
+    assert!(HostError::result_matches_err(&b + &three, code));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn test_u256_ilog() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let ilog2 = |x: U256| -> Result<u32, HostError> {
+        Ok(host.u256_ilog2(U256Val::try_from_val(&host, &x)?)?.into())
+    };
+    let ilog = |x: U256, base: U256| -> Result<u32, HostError> {
+        let x = U256Val::try_from_val(&host, &x)?;
+        let base = U256Val::try_from_val(&host, &base)?;
+        Ok(host.u256_ilog(x, base)?.into())
+    };
+
+    // exact powers of two
+    assert_eq!(ilog2(U256::ONE)?, 0);
+    assert_eq!(ilog2(U256::new(1024))?, 10);
+    assert_eq!(ilog2(U256::ONE << 200)?, 200);
+    assert_eq!(ilog(U256::new(1024), U256::new(2))?, 10);
+
+    // non-powers round down
+    assert_eq!(ilog2(U256::new(1023))?, 9);
+    assert_eq!(ilog2(U256::MAX)?, 255);
+    assert_eq!(ilog(U256::new(999), U256::new(10))?, 2);
+    assert_eq!(ilog(U256::new(1000), U256::new(10))?, 3);
+    assert_eq!(ilog(U256::new(5), U256::new(7))?, 0);
+    assert_eq!(ilog(U256::MAX, U256::new(2))?, 255);
+
+    // zero value or bad base
+    let code = (ScErrorType::Object, ScErrorCode::ArithDomain);
+    assert!(HostError::result_matches_err(ilog2(U256::ZERO), code));
+    assert!(HostError::result_matches_err(
+        ilog(U256::ZERO, U256::new(10)),
+        code
+    ));
+    assert!(HostError::result_matches_err(
+        ilog(U256::new(10), U256::ONE),
+        code
+    ));
+    assert!(HostError::result_matches_err(
+        ilog(U256::new(10), U256::ZERO),
+        code
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn test_bitset_bits() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let get = |bits: u64, i: u32| -> Result<bool, HostError> {
+        let bits = U64Val::try_from_val(&host, &bits)?;
+        Ok(host.bitset_get_bit(bits, i.into())?.into())
+    };
+    let set = |bits: u64, i: u32, v: bool| -> Result<u64, HostError> {
+        let bits = U64Val::try_from_val(&host, &bits)?;
+        let res = host.bitset_set_bit(bits, i.into(), v.into())?;
+        Ok(res.try_into_val(&host)?)
+    };
+
+    assert!(get(0b101, 0)?);
+    assert!(!get(0b101, 1)?);
+    assert!(get(0b101, 2)?);
+    assert!(get(u64::MAX, 63)?);
+    assert_eq!(set(0, 3, true)?, 0b1000);
+    assert_eq!(set(0b1111, 1, false)?, 0b1101);
+    assert_eq!(set(0b1000, 3, true)?, 0b1000);
+    // Setting the top bit moves the value from a small to an object U64Val,
+    // which must round-trip through `ScVal` unchanged.
+    let top = set(0, 63, true)?;
+    assert_eq!(top, 1 << 63);
+    let val = U64Val::try_from_val(&host, &top)?.to_val();
+    let scv = ScVal::try_from_val(&host, &val)?;
+    assert_eq!(scv, ScVal::U64(1 << 63));
+    let back: Val = Val::try_from_val(&host, &scv)?;
+    assert!(get(back.try_into_val(&host)?, 63)?);
+
+    let code = (ScErrorType::Value, ScErrorCode::IndexBounds);
+    assert!(HostError::result_matches_err(get(1, 64), code));
+    assert!(HostError::result_matches_err(set(1, 100, true), code));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn test_i64_to_i32_checked() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let narrow = |i: i64| -> Result<i32, HostError> {
+        let x: I64Val = Val::try_from_val(&host, &i)?.try_into()?;
+        Ok(host.i64_to_i32_checked(x)?.into())
+    };
+    let widen = |i: i32| -> Result<i64, HostError> {
+        Ok(i64::try_from_val(
+            &host,
+            &host.i32_to_i64(i.into())?.to_val(),
+        )?)
+    };
+
+    assert_eq!(narrow(-7)?, -7);
+    assert_eq!(narrow(i32::MAX as i64)?, i32::MAX);
+    assert_eq!(narrow(i32::MIN as i64)?, i32::MIN);
+    let code = (ScErrorType::Value, ScErrorCode::ArithDomain);
+    assert!(HostError::result_matches_err(
+        narrow(i32::MAX as i64 + 1),
+        code
+    ));
+    assert!(HostError::result_matches_err(
+        narrow(i32::MIN as i64 - 1),
+        code
+    ));
+    // Values large enough to be stored as objects are rejected too.
+    assert!(HostError::result_matches_err(narrow(i64::MAX), code));
+
+    for i in [0, 1, -1, i32::MAX, i32::MIN] {
+        assert_eq!(widen(i)?, i as i64);
+        assert_eq!(narrow(widen(i)?)?, i);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn test_u64_bit_counts() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let counts = |x: u64| -> Result<[u32; 3], HostError> {
+        let x = U64Val::try_from_val(&host, &x)?;
+        Ok([
+            host.u64_clz(x)?.into(),
+            host.u64_ctz(x)?.into(),
+            host.u64_popcnt(x)?.into(),
+        ])
+    };
+
+    assert_eq!(counts(0b1011)?, [60, 0, 3]);
+    assert_eq!(counts(1)?, [63, 0, 1]);
+    assert_eq!(counts(0)?, [64, 64, 0]);
+    // Values large enough to be stored as objects.
+    assert_eq!(counts(u64::MAX)?, [0, 0, 64]);
+    assert_eq!(counts(1 << 63)?, [0, 63, 1]);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn test_i256_saturating_add_sub() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let v = |i: I256| -> Result<I256Val, HostError> { Ok(I256Val::try_from_val(&host, &i)?) };
+    let n = |i: i64| I256::from(i);
+    let add = |a: I256, b: I256, lo: I256, hi: I256| -> Result<I256, HostError> {
+        let res = host.i256_add_sat(v(a)?, v(b)?, v(lo)?, v(hi)?)?;
+        Ok(res.to_val().try_into_val(&host)?)
+    };
+    let sub = |a: I256, b: I256, lo: I256, hi: I256| -> Result<I256, HostError> {
+        let res = host.i256_sub_sat(v(a)?, v(b)?, v(lo)?, v(hi)?)?;
+        Ok(res.to_val().try_into_val(&host)?)
+    };
+
+    // In range, the result is exact.
+    assert_eq!(add(n(40), n(2), n(0), n(100))?, n(42));
+    assert_eq!(sub(n(40), n(50), n(-100), n(100))?, n(-10));
+
+    // Out of range, the result saturates at the nearer bound.
+    assert_eq!(add(n(90), n(20), n(0), n(100))?, n(100));
+    assert_eq!(sub(n(10), n(20), n(0), n(100))?, n(0));
+
+    // Results past the i256 range itself still saturate correctly.
+    assert_eq!(add(I256::MAX, n(1), n(0), n(100))?, n(100));
+    assert_eq!(sub(I256::MIN, n(1), n(-100), n(0))?, n(-100));
+    assert_eq!(add(I256::MAX, I256::MAX, I256::MIN, I256::MAX)?, I256::MAX);
+
+    assert!(HostError::result_matches_err(
+        add(n(1), n(1), n(10), n(0)),
+        (ScErrorType::Object, ScErrorCode::InvalidInput)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn test_u64_add_mul_mod() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let v = |x: u64| -> Result<U64Val, HostError> { Ok(U64Val::try_from_val(&host, &x)?) };
+    let add = |a: u64, b: u64, m: u64| -> Result<u64, HostError> {
+        Ok(host.u64_add_mod(v(a)?, v(b)?, v(m)?)?.try_into_val(&host)?)
+    };
+    let mul = |a: u64, b: u64, m: u64| -> Result<u64, HostError> {
+        Ok(host.u64_mul_mod(v(a)?, v(b)?, v(m)?)?.try_into_val(&host)?)
+    };
+
+    assert_eq!(add(5, 4, 7)?, 2);
+    assert_eq!(mul(5, 4, 7)?, 6);
+
+    // Intermediates near u64::MAX don't overflow.
+    let max = u64::MAX;
+    assert_eq!(add(max, max, max)?, 0);
+    assert_eq!(add(max, 1, max)?, 1);
+    assert_eq!(add(max, max, 10)?, ((max as u128 * 2) % 10) as u64);
+    assert_eq!(mul(max, max, max - 1)?, 1);
+    assert_eq!(mul(max, max, 1 << 63)?, 1);
+
+    // A modulus of 1 wraps everything to 0.
+    assert_eq!(mul(max, 3, 1)?, 0);
+
+    assert!(HostError::result_matches_err(
+        host.u64_add_mod(v(1)?, v(2)?, v(0)?),
+        (ScErrorType::Value, ScErrorCode::ArithDomain)
+    ));
+    assert!(HostError::result_matches_err(
+        host.u64_mul_mod(v(1)?, v(2)?, v(0)?),
+        (ScErrorType::Value, ScErrorCode::ArithDomain)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn test_i256_to_i64_checked() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let v = |i: I256| -> Result<I256Val, HostError> { Ok(I256Val::try_from_val(&host, &i)?) };
+    let narrow = |i: I256| -> Result<i64, HostError> {
+        Ok(host.i256_to_i64_checked(v(i)?)?.try_into_val(&host)?)
+    };
+
+    // In-range values round-trip, whether small or objects on either side.
+    for i in [0, -1, 42, i64::MIN, i64::MAX, 1 << 60, -(1 << 60)] {
+        assert_eq!(narrow(I256::from(i))?, i);
+    }
+
+    for i in [
+        I256::from(u64::MAX) << 1,
+        I256::from(i64::MAX) + I256::ONE,
+        I256::from(i64::MIN) - I256::ONE,
+        I256::MAX,
+        I256::MIN,
+    ] {
+        assert!(HostError::result_matches_err(
+            host.i256_to_i64_checked(v(i)?),
+            (ScErrorType::Value, ScErrorCode::ArithDomain)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn test_i256_byte_len() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let byte_len = |i: I256| -> Result<u32, HostError> {
+        Ok(host
+            .i256_byte_len(I256Val::try_from_val(&host, &i)?)?
+            .into())
+    };
+    assert_eq!(byte_len(I256::ZERO)?, 0);
+    assert_eq!(byte_len(I256::from(1))?, 1);
+    assert_eq!(byte_len(I256::from(255))?, 1);
+    assert_eq!(byte_len(I256::from(256))?, 2);
+    assert_eq!(byte_len(I256::from(-256))?, 2);
+    assert_eq!(byte_len(I256::from(u64::MAX))?, 8);
+    assert_eq!(byte_len(I256::from(u64::MAX) + I256::ONE)?, 9);
+    assert_eq!(byte_len(I256::MAX)?, 32);
+    assert_eq!(byte_len(I256::MIN)?, 32);
+
+    // The length agrees with the significant bytes of the big-endian form.
+    for i in [
+        I256::from(1),
+        I256::from(256),
+        I256::from(-70_000),
+        I256::MAX,
+    ] {
+        let x = I256Val::try_from_val(&host, &i.abs())?;
+        let bytes = host.i256_val_to_be_bytes(x)?;
+        let mut be = [0_u8; 32];
+        host.bytes_copy_to_slice(bytes, U32Val::from(0), &mut be)?;
+        let significant = be.iter().skip_while(|b| **b == 0).count() as u32;
+        assert_eq!(byte_len(i)?, significant);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_128_bit_hi_lo_round_trip() -> Result<(), HostError> {
+    let host = Host::test_host();
+    for x in [i128::MAX, i128::MIN, -(1_i128 << 100), i128::MIN + 12345] {
+        let obj = host.add_host_object(x)?;
+        let hi = host.obj_to_i128_hi64(obj)?;
+        let lo = host.obj_to_i128_lo64(obj)?;
+        assert_eq!(hi, (x >> 64) as i64);
+        assert_eq!(lo, x as u64);
+        let back = host.obj_from_i128_pieces(hi, lo)?;
+        assert_eq!(host.obj_cmp(back.to_val(), obj.to_val())?, 0);
+        assert_eq!(((hi as i128) << 64) | lo as i128, x);
+    }
+    let obj = host.add_host_object(i128::MAX)?;
+    assert_eq!(host.obj_to_i128_hi64(obj)?, i64::MAX);
+    assert_eq!(host.obj_to_i128_lo64(obj)?, u64::MAX);
+
+    for x in [u128::MAX, 1_u128 << 100, (1_u128 << 64) + 7] {
+        let obj = host.add_host_object(x)?;
+        let hi = host.obj_to_u128_hi64(obj)?;
+        let lo = host.obj_to_u128_lo64(obj)?;
+        assert_eq!((hi, lo), ((x >> 64) as u64, x as u64));
+        let back = host.obj_from_u128_pieces(hi, lo)?;
+        assert_eq!(host.obj_cmp(back.to_val(), obj.to_val())?, 0);
+    }
+    Ok(())
+}
@@ -259,6 +259,40 @@ fn host_and_contract_with_seed(
     Ok((host, id))
 }
 
+// Two hosts configured with the same base seed shuffle the same vector into
+// the same order, and the shuffle is a permutation of its input.
+#[test]
+fn prng_vec_shuffle_is_deterministic() -> Result<(), HostError> {
+    let shuffle = || -> Result<Vec<i32>, HostError> {
+        let host = Host::test_host_with_recording_footprint();
+        host.set_base_prng_seed([7; 32])?;
+        let id = PRNGUsingTest::register_as(&host, &[0; 32]);
+        let input = host.test_scvec::<i32>(&[1, 2, 2, 3, 5, 8, 13, 21])?;
+        let args = host.test_vec_obj::<ScVec>(&[input])?;
+        let res: VecObject = host.call(id, SHUFFLE.into(), args)?.try_into()?;
+        let ScVal::Vec(Some(scv)) = ScVal::from(host.from_host_obj(res)?) else {
+            panic!("from_host_obj(VecObject) produced non-Some(ScVec(...))");
+        };
+        Ok(scv
+            .0
+            .iter()
+            .map(|x| match x {
+                ScVal::I32(i) => *i,
+                _ => panic!("shuffle produced a non-i32 element"),
+            })
+            .collect())
+    };
+
+    let order0 = shuffle()?;
+    let order1 = shuffle()?;
+    assert_eq!(order0, order1);
+
+    let mut sorted = order0.clone();
+    sorted.sort();
+    assert_eq!(sorted, vec![1, 2, 2, 3, 5, 8, 13, 21]);
+    Ok(())
+}
+
 // This test checks that setting the base seed to two different values
 // produces _frame_ PRNG behaviour that differs; and that setting it
 // to the same value twice produces the same behaviour both times.
@@ -2,8 +2,9 @@ use crate::{
     meta::INTERFACE_VERSION,
     testutils::{generate_account_id, generate_bytes_array, wasm as wasm_util},
     xdr::{ScErrorCode, ScErrorType},
-    AddressObject, Env, Host, HostError, LedgerInfo, Symbol, Val, WasmiMarshal,
+    AddressObject, Env, EnvBase, Host, HostError, LedgerInfo, Symbol, Val, WasmiMarshal,
 };
+use soroban_synth_wasm::{Arity, ModEmitter};
 
 #[test]
 fn ledger_protocol_greater_than_env_protocol_should_fail() -> Result<(), HostError> {
@@ -300,3 +301,65 @@ fn test_additional_protocol_guardrail_during_invocation() -> Result<(), HostErro
     );
     Ok(())
 }
+
+#[test]
+fn get_ledger_version_matches_protocol_gating() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let mut li = LedgerInfo::default();
+
+    // The version contracts read is the one host function gating checks:
+    // `protocol_gated_dummy` is only available at protocol 19.
+    li.protocol_version = 18;
+    host.set_ledger_info(li.clone())?;
+    assert_eq!(u32::from(host.get_ledger_version()?), 18);
+    assert!(HostError::result_matches_err(
+        <Host as Env>::protocol_gated_dummy(&host),
+        (ScErrorType::Context, ScErrorCode::IndexBounds)
+    ));
+
+    li.protocol_version = 19;
+    host.set_ledger_info(li)?;
+    assert_eq!(u32::from(host.get_ledger_version()?), 19);
+    assert!(<Host as Env>::protocol_gated_dummy(&host).is_ok());
+    Ok(())
+}
+
+#[test]
+fn host_functions_added_in_protocol_24_are_gated() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    host.enable_debug()?;
+    let mut li = LedgerInfo::default();
+
+    // `bytes_trim` is only available from protocol 24, so a protocol 23
+    // ledger rejects both native calls and Wasm imports of it.
+    li.protocol_version = 23;
+    host.set_ledger_info(li.clone())?;
+    let b = host.bytes_new_from_slice(&[0, 5, 0])?;
+    assert!(HostError::result_matches_err(
+        <Host as Env>::bytes_trim(&host, b, 0u32.into()),
+        (ScErrorType::Context, ScErrorCode::IndexBounds)
+    ));
+
+    let mut me = ModEmitter::new();
+    me.add_protocol_version_meta(23);
+    me.import_func("b", "C", Arity(2));
+    let mut fe = me.func(Arity(0), 0);
+    fe.push(Symbol::try_from_small_str("pass")?);
+    let wasm = fe.finish_and_export("test").finish();
+    let id = host.register_test_contract_wasm_from_source_account(
+        wasm.as_slice(),
+        generate_account_id(&host),
+        generate_bytes_array(&host),
+    );
+    assert!(HostError::result_matches_err(
+        id,
+        (ScErrorType::WasmVm, ScErrorCode::InvalidAction)
+    ));
+
+    if INTERFACE_VERSION.protocol >= 24 {
+        li.protocol_version = 24;
+        host.set_ledger_info(li)?;
+        assert!(<Host as Env>::bytes_trim(&host, b, 0u32.into()).is_ok());
+    }
+    Ok(())
+}
@@ -368,6 +368,61 @@ fn test_stellar_asset_contract_smart_roundtrip() {
     );
 }
 
+#[cfg(feature = "next")]
+#[test]
+fn test_native_asset_contract_id_queries_native_balance() {
+    use xdr::WriteXdr;
+    let test = StellarAssetContractTest::setup(function_name!());
+    let account_id = signing_key_to_account_id(&test.user_key);
+    test.create_account(
+        &account_id,
+        vec![(&test.user_key, 100)],
+        100_000_000,
+        1,
+        [1, 0, 0, 0],
+        None,
+        None,
+        0,
+    );
+    let contract = TestStellarAssetContract::new_from_asset(&test.host, Asset::Native).unwrap();
+
+    // The native asset contract's id matches the deployed native contract and
+    // the id of the serialized native asset.
+    let native = test.host.get_native_asset_contract_id().unwrap();
+    assert_eq!(
+        test.host
+            .visit_obj(native, |a: &ScAddress| Ok(a.clone()))
+            .unwrap(),
+        contract.address.to_sc_address().unwrap()
+    );
+    let serialized = test
+        .host
+        .bytes_new_from_slice(&Asset::Native.to_xdr(xdr::Limits::none()).unwrap())
+        .unwrap();
+    let by_asset = test.host.get_asset_contract_id(serialized).unwrap();
+    assert_eq!(
+        test.host
+            .obj_cmp(native.to_val(), by_asset.to_val())
+            .unwrap(),
+        0
+    );
+
+    // Querying it reads the account's native balance.
+    let user = test
+        .host
+        .add_host_object(ScAddress::Account(account_id.clone()))
+        .unwrap();
+    let args = test.host.vec_new_from_slice(&[user.to_val()]).unwrap();
+    let balance: i128 = test
+        .host
+        .call(native, Symbol::try_from_small_str("balance").unwrap(), args)
+        .unwrap()
+        .try_into_val(&test.host)
+        .unwrap();
+    assert_eq!(balance, 100_000_000);
+    assert_eq!(test.get_native_balance(&account_id), 100_000_000);
+}
+
 fn create_asset(issuer_id: &AccountId, asset_code: &[u8]) -> Asset {
     if asset_code.len() == 4 {
         let mut code = [0_u8; 4];
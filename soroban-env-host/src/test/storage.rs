@@ -477,3 +477,524 @@ fn test_large_instance_key() {
         test_vec![&*host, key, 1_u64].into(),
     );
 }
+
+#[cfg(feature = "next")]
+#[test]
+fn incr_contract_data_counter() -> Result<(), HostError> {
+    use crate::{xdr::Hash, StorageType, U64Val, Val};
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    host.with_test_contract_frame(id, Symbol::try_from_small_str("incr")?, || {
+        let key: Val = Symbol::try_from_small_str("count")?.into();
+        let incr = |delta: u64| -> Result<u64, HostError> {
+            let delta = U64Val::try_from_val(&host, &delta)?;
+            Ok(host.incr_contract_data(key, delta)?.try_into_val(&host)?)
+        };
+        let stored = || -> Result<u64, HostError> {
+            Ok(host
+                .get_contract_data(key, StorageType::Persistent)?
+                .try_into_val(&host)?)
+        };
+
+        // A missing counter starts from 0.
+        assert_eq!(incr(1)?, 1);
+        assert_eq!(incr(2)?, 3);
+        assert_eq!(incr(3)?, 6);
+        assert_eq!(stored()?, 6);
+
+        // Sums past u64::MAX are rejected and leave the counter unchanged.
+        assert_eq!(incr(u64::MAX - 7)?, u64::MAX - 1);
+        assert_eq!(incr(1)?, u64::MAX);
+        assert!(HostError::result_matches_err(
+            incr(1),
+            (ScErrorType::Value, ScErrorCode::ArithDomain)
+        ));
+        assert_eq!(stored()?, u64::MAX);
+
+        // The stored value must be a u64.
+        host.put_contract_data(
+            key,
+            Symbol::try_from_small_str("x")?.into(),
+            StorageType::Persistent,
+        )?;
+        assert!(HostError::result_matches_err(
+            incr(1),
+            (ScErrorType::Value, ScErrorCode::UnexpectedType)
+        ));
+        Ok(Val::VOID.into())
+    })?;
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn contract_exists_checks_instance_entry() -> Result<(), HostError> {
+    use crate::xdr::Hash;
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    assert!(bool::from(host.contract_exists(contract)?));
+
+    // An id that was never deployed is reported as absent, and its instance
+    // key is still recorded in the footprint.
+    let missing_id = Hash([0xab; 32]);
+    let missing = host.add_host_object(ScAddress::Contract(missing_id.clone()))?;
+    assert!(!bool::from(host.contract_exists(missing)?));
+    let key = host.contract_instance_ledger_key(&missing_id)?;
+    assert!(host
+        .try_borrow_storage()?
+        .footprint
+        .0
+        .contains_key::<LedgerKey>(&key, host.as_budget())?);
+
+    // Account addresses are not contracts.
+    let account = host.add_host_object(ScAddress::Account(
+        crate::testutils::generate_account_id(&host),
+    ))?;
+    assert!(host.contract_exists(account).is_err());
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn get_and_incr_nonce_counts_per_address() -> Result<(), HostError> {
+    use crate::{
+        xdr::{Hash, LedgerEntryData, ScVec},
+        EnvBase, StorageType, Val,
+    };
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    let a = host.add_host_object(ScAddress::Contract(Hash([1; 32])))?;
+    let b = host.add_host_object(ScAddress::Contract(Hash([2; 32])))?;
+    host.with_test_contract_frame(id, Symbol::try_from_small_str("nonce")?, || {
+        let nonce = |addr: AddressObject| -> Result<u64, HostError> {
+            Ok(host.get_and_incr_nonce(addr)?.try_into_val(&host)?)
+        };
+        assert_eq!(nonce(a)?, 0);
+        assert_eq!(nonce(a)?, 1);
+        // Each address has its own nonce.
+        assert_eq!(nonce(b)?, 0);
+
+        // The stored nonce is the next one to hand out.
+        let sc_address = host.scaddress_from_address(a)?;
+        let key = host.nonce_ledger_key(&sc_address)?;
+        let entry = host
+            .try_borrow_storage_mut()?
+            .get_with_host(&key, &host, None)?;
+        let LedgerEntryData::ContractData(data) = &entry.data else {
+            panic!("expected a contract data entry");
+        };
+        let expected = ScVal::Vec(Some(ScVec(
+            vec![ScVal::Address(sc_address), ScVal::U64(2)].try_into()?,
+        )));
+        assert_eq!(data.val, expected);
+
+        // Nonces don't use the contract's own keys, so an SDK-style
+        // `Nonce(Address)` entry is left alone.
+        let sdk_key =
+            host.vec_new_from_slice(&[Symbol::try_from_small_str("Nonce")?.to_val(), a.to_val()])?;
+        host.put_contract_data(sdk_key.to_val(), 7_u32.into(), StorageType::Persistent)?;
+        assert_eq!(nonce(a)?, 2);
+        let sdk_val = host.get_contract_data(sdk_key.to_val(), StorageType::Persistent)?;
+        assert_eq!(u32::try_from(sdk_val)?, 7);
+        Ok(Val::VOID.into())
+    })?;
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn get_contract_data_batch_reads_present_and_absent_keys() -> Result<(), HostError> {
+    use crate::{xdr::Hash, EnvBase, StorageType, Val};
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    let [a, b, c] = ["a", "b", "c"].map(|s| Symbol::try_from_small_str(s).unwrap().to_val());
+
+    host.with_test_contract_frame(id, Symbol::try_from_small_str("get")?, || {
+        for t in [StorageType::Persistent, StorageType::Instance] {
+            host.put_contract_data(a, 1_u32.into(), t)?;
+            host.put_contract_data(c, 3_u32.into(), t)?;
+            let keys = host.vec_new_from_slice(&[a, b, c])?;
+            let vals = host.get_contract_data_batch(keys, t)?;
+            assert_eq!(u32::from(host.vec_len(vals)?), 3);
+            let get = |i: u32| host.vec_get(vals, i.into());
+            assert_eq!(u32::try_from(get(0)?)?, 1);
+            assert!(get(1)?.is_void());
+            assert_eq!(u32::try_from(get(2)?)?, 3);
+        }
+        Ok(Val::VOID.into())
+    })?;
+    Ok(())
+}
+
+#[test]
+fn max_entry_size_rejects_oversized_values() -> Result<(), HostError> {
+    use crate::{xdr::Hash, EnvBase, StorageType, Val};
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    let [a, b] = ["a", "b"].map(|s| Symbol::try_from_small_str(s).unwrap().to_val());
+    let t = StorageType::Persistent;
+    host.set_max_entry_size(32)?;
+
+    host.with_test_contract_frame(id, Symbol::try_from_small_str("put")?, || {
+        host.put_contract_data(a, 1_u32.into(), t)?;
+        assert_eq!(u32::try_from(host.get_contract_data(a, t)?)?, 1);
+
+        let big = host.bytes_new_from_slice(&[0; 100])?.to_val();
+        let oversized = (ScErrorType::Storage, ScErrorCode::ExceededLimit);
+        assert!(HostError::result_matches_err(
+            host.put_contract_data(b, big, t),
+            oversized
+        ));
+        assert!(!bool::from(host.has_contract_data(b, t)?));
+
+        // Overwriting an existing entry is limited too.
+        assert!(HostError::result_matches_err(
+            host.put_contract_data(a, big, t),
+            oversized
+        ));
+        assert_eq!(u32::try_from(host.get_contract_data(a, t)?)?, 1);
+        Ok(Val::VOID.into())
+    })?;
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn put_contract_data_batch_is_all_or_nothing() -> Result<(), HostError> {
+    use crate::{xdr::Hash, EnvBase, StorageType, Val};
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    let [a, b, c] = ["a", "b", "c"].map(|s| Symbol::try_from_small_str(s).unwrap().to_val());
+    let batch = |entries: &[(Val, u32)]| -> Result<_, HostError> {
+        let mut pairs = vec![];
+        for (k, v) in entries {
+            pairs.push(
+                host.vec_new_from_slice(&[*k, Val::from_u32(*v).to_val()])?
+                    .to_val(),
+            );
+        }
+        host.vec_new_from_slice(&pairs)
+    };
+    let get = |k: Val| -> Result<u32, HostError> {
+        Ok(host
+            .get_contract_data(k, StorageType::Persistent)?
+            .try_into_val(&host)?)
+    };
+
+    // Record `a` and `b` as read-write in the footprint, then enforce it.
+    host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("put")?, || {
+        host.put_contract_data_batch(batch(&[(a, 1), (b, 1)])?, StorageType::Persistent)?;
+        Ok(Val::VOID.into())
+    })?;
+    host.switch_to_enforcing_storage()?;
+
+    host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("put")?, || {
+        host.put_contract_data_batch(batch(&[(a, 2), (b, 2)])?, StorageType::Persistent)?;
+        assert_eq!(get(a)?, 2);
+        assert_eq!(get(b)?, 2);
+        Ok(Val::VOID.into())
+    })?;
+
+    // `c` is not in the footprint, so neither entry of the batch is written.
+    host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("put")?, || {
+        let res = host.put_contract_data_batch(batch(&[(a, 3), (c, 3)])?, StorageType::Persistent);
+        assert!(HostError::result_matches_err(
+            res,
+            (ScErrorType::Storage, ScErrorCode::ExceededLimit)
+        ));
+        assert_eq!(get(a)?, 2);
+        Ok(Val::VOID.into())
+    })?;
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn put_contract_data_batch_checks_every_size_first() -> Result<(), HostError> {
+    use crate::{xdr::Hash, EnvBase, StorageType, Val};
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    let [a, b] = ["a", "b"].map(|s| Symbol::try_from_small_str(s).unwrap().to_val());
+    let t = StorageType::Persistent;
+    host.set_max_entry_size(32)?;
+
+    host.with_test_contract_frame(id, Symbol::try_from_small_str("put")?, || {
+        let big = host.bytes_new_from_slice(&[0; 100])?.to_val();
+        let small_pair = host.vec_new_from_slice(&[a, 1_u32.into()])?.to_val();
+        let big_pair = host.vec_new_from_slice(&[b, big])?.to_val();
+        let batch = host.vec_new_from_slice(&[small_pair, big_pair])?;
+        assert!(HostError::result_matches_err(
+            host.put_contract_data_batch(batch, t),
+            (ScErrorType::Storage, ScErrorCode::ExceededLimit)
+        ));
+        // The small value comes first but is not written either.
+        assert!(!bool::from(host.has_contract_data(a, t)?));
+        assert!(!bool::from(host.has_contract_data(b, t)?));
+        Ok(Val::VOID.into())
+    })?;
+    Ok(())
+}
+
+#[test]
+fn hash_ledger_key_is_sha256_of_xdr() -> Result<(), HostError> {
+    use crate::xdr::{Limits, WriteXdr};
+    use sha2::{Digest, Sha256};
+    let host = Host::test_host();
+    let key = LedgerKey::ContractData(LedgerKeyContractData {
+        contract: ScAddress::Contract([7; 32].into()),
+        key: ScVal::Symbol("counter".try_into().unwrap()),
+        durability: ContractDataDurability::Persistent,
+    });
+    let xdr = key.to_xdr(Limits::none()).unwrap();
+    let expected: [u8; 32] = Sha256::digest(&xdr).into();
+    assert_eq!(host.hash_ledger_key(&key)?.0, expected);
+    Ok(())
+}
+
+#[test]
+fn staged_contract_data_commit_and_discard() -> Result<(), HostError> {
+    use crate::{xdr::Hash, EnvBase, StorageType, Val};
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    let a = Symbol::try_from_small_str("a")?.to_val();
+    let b = Symbol::try_from_small_str("b")?.to_val();
+    host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("stage")?, || {
+        let has = |k: Val| -> Result<bool, HostError> {
+            Ok(host.has_contract_data(k, StorageType::Persistent)?.into())
+        };
+        host.stage_contract_data(a, Val::from_u32(1).into(), StorageType::Persistent)?;
+        host.stage_contract_data(b, Val::from_u32(2).into(), StorageType::Persistent)?;
+        assert!(!has(a)?);
+
+        // Discarded writes are never applied, even by a later commit.
+        host.discard_staged()?;
+        host.commit_staged()?;
+        assert!(!has(a)?);
+        assert!(!has(b)?);
+
+        host.stage_contract_data(a, Val::from_u32(1).into(), StorageType::Persistent)?;
+        host.stage_contract_data(b, Val::from_u32(2).into(), StorageType::Persistent)?;
+        host.commit_staged()?;
+        let get = |k: Val| -> Result<u32, HostError> {
+            Ok(host
+                .get_contract_data(k, StorageType::Persistent)?
+                .try_into_val(&host)?)
+        };
+        assert_eq!(get(a)?, 1);
+        assert_eq!(get(b)?, 2);
+
+        // Instance storage writes can't be staged.
+        assert!(HostError::result_matches_err(
+            host.stage_contract_data(a, Val::from_u32(3).into(), StorageType::Instance),
+            (ScErrorType::Storage, ScErrorCode::InvalidAction)
+        ));
+
+        // Writes staged by an inner frame belong to it: they aren't seen by
+        // the outer frame's commit, whether the inner frame fails or returns
+        // without committing them.
+        host.stage_contract_data(a, Val::from_u32(5).into(), StorageType::Persistent)?;
+        let res =
+            host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("inner")?, || {
+                host.stage_contract_data(b, Val::from_u32(6).into(), StorageType::Persistent)?;
+                Err(host.err(
+                    ScErrorType::Context,
+                    ScErrorCode::InvalidAction,
+                    "inner frame fails",
+                    &[],
+                ))
+            });
+        assert!(res.is_err());
+        host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("inner")?, || {
+            host.stage_contract_data(b, Val::from_u32(7).into(), StorageType::Persistent)?;
+            Ok(Val::VOID.into())
+        })?;
+        host.commit_staged()?;
+        assert_eq!(get(a)?, 5);
+        assert_eq!(get(b)?, 2);
+        Ok(Val::VOID.into())
+    })?;
+    Ok(())
+}
+
+#[test]
+fn staged_contract_data_commit_checks_every_key_first() -> Result<(), HostError> {
+    use crate::{xdr::Hash, EnvBase, StorageType, Val};
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    let [a, b] = ["a", "b"].map(|s| Symbol::try_from_small_str(s).unwrap().to_val());
+    let get = |k: Val| -> Result<u32, HostError> {
+        Ok(host
+            .get_contract_data(k, StorageType::Persistent)?
+            .try_into_val(&host)?)
+    };
+
+    // Record `a` as read-write in the footprint, then enforce it.
+    host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("put")?, || {
+        host.put_contract_data(a, Val::from_u32(1).into(), StorageType::Persistent)?;
+        Ok(Val::VOID.into())
+    })?;
+    host.switch_to_enforcing_storage()?;
+
+    // `b` is not in the footprint, so the commit writes nothing and the
+    // writes stay staged until they are discarded.
+    host.with_test_contract_frame(id, Symbol::try_from_small_str("stage")?, || {
+        host.stage_contract_data(a, Val::from_u32(2).into(), StorageType::Persistent)?;
+        host.stage_contract_data(b, Val::from_u32(2).into(), StorageType::Persistent)?;
+        let code = (ScErrorType::Storage, ScErrorCode::ExceededLimit);
+        assert!(HostError::result_matches_err(host.commit_staged(), code));
+        assert_eq!(get(a)?, 1);
+        assert!(HostError::result_matches_err(host.commit_staged(), code));
+        host.discard_staged()?;
+        host.commit_staged()?;
+        assert_eq!(get(a)?, 1);
+        Ok(Val::VOID.into())
+    })?;
+    Ok(())
+}
+
+#[test]
+fn failed_inner_frame_writes_are_rolled_back() -> Result<(), HostError> {
+    use crate::{xdr::Hash, EnvBase, StorageType, Val};
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    let outer_key = Symbol::try_from_small_str("outer")?.to_val();
+    let inner_key = Symbol::try_from_small_str("inner")?.to_val();
+    let one = Val::from_u32(1).to_val();
+    host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("outer")?, || {
+        let has = |k: Val| -> Result<bool, HostError> {
+            Ok(host.has_contract_data(k, StorageType::Persistent)?.into())
+        };
+        host.put_contract_data(outer_key, one, StorageType::Persistent)?;
+
+        // An inner frame that writes and then fails leaves no trace of its
+        // write, and the outer frame's write is untouched.
+        let res =
+            host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("inner")?, || {
+                host.put_contract_data(inner_key, one, StorageType::Persistent)?;
+                assert!(has(inner_key)?);
+                Err(host.err(
+                    ScErrorType::Context,
+                    ScErrorCode::InvalidAction,
+                    "inner frame fails",
+                    &[],
+                ))
+            });
+        assert!(HostError::result_matches_err(
+            res,
+            (ScErrorType::Context, ScErrorCode::InvalidAction)
+        ));
+        assert!(!has(inner_key)?);
+        assert!(has(outer_key)?);
+
+        // A successful inner frame's write persists.
+        host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("inner")?, || {
+            host.put_contract_data(inner_key, one, StorageType::Persistent)?;
+            Ok(Val::VOID.into())
+        })?;
+        assert!(has(inner_key)?);
+        Ok(Val::VOID.into())
+    })?;
+    Ok(())
+}
+
+#[test]
+fn staged_write_bytes_sums_read_write_entries() -> Result<(), HostError> {
+    use crate::{
+        xdr::{Hash, Limits, WriteXdr},
+        EnvBase, StorageType, Val,
+    };
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    // Registering the contract writes its code and instance entries.
+    let before = host.staged_write_bytes()?;
+    assert!(before > 0);
+
+    let keys = [
+        Symbol::try_from_small_str("a")?.to_val(),
+        Symbol::try_from_small_str("b")?.to_val(),
+    ];
+    host.with_test_contract_frame(id, Symbol::try_from_small_str("put")?, || {
+        let big = host.bytes_new_from_slice(&[7; 100])?;
+        host.put_contract_data(keys[0], Val::from_u32(1).into(), StorageType::Persistent)?;
+        host.put_contract_data(keys[1], big.into(), StorageType::Temporary)?;
+        Ok(Val::VOID.into())
+    })?;
+
+    let mut expected = 0;
+    for (k, t) in [
+        (keys[0], ContractDataDurability::Persistent),
+        (keys[1], ContractDataDurability::Temporary),
+    ] {
+        let key = Rc::new(LedgerKey::ContractData(LedgerKeyContractData {
+            contract: ScAddress::Contract(id.clone()),
+            key: host.from_host_val(k)?,
+            durability: t,
+        }));
+        let (entry, _) = host
+            .try_borrow_storage_mut()?
+            .get_with_live_until_ledger(&key, &host, None)?;
+        expected += entry.to_xdr(Limits::none()).unwrap().len() as u64;
+    }
+    assert!(expected > 100);
+    assert_eq!(host.staged_write_bytes()? - before, expected);
+    Ok(())
+}
+
+#[test]
+fn footprint_covers_recorded_accesses() -> Result<(), HostError> {
+    use crate::{xdr::Hash, StorageType, Val};
+    let host = Host::test_host_with_recording_footprint();
+    let contract_id = host.register_test_contract_wasm(CONTRACT_STORAGE);
+    let id: Hash = host.contract_id_from_address(contract_id)?;
+    host.with_test_contract_frame(id.clone(), Symbol::try_from_small_str("put")?, || {
+        let key: Val = Symbol::try_from_small_str("a")?.into();
+        host.put_contract_data(key, key, StorageType::Persistent)?;
+        Ok(Val::VOID.into())
+    })?;
+    let budget = host.as_budget();
+    let recorded = host.try_borrow_storage()?.footprint.clone();
+    assert!(recorded
+        .0
+        .map
+        .iter()
+        .any(|(_, ty)| *ty == AccessType::ReadWrite));
+
+    // The recorded footprint covers itself, and so does a superset of it.
+    assert!(host.footprint_covers(&recorded)?);
+    let mut superset = recorded.clone();
+    let extra = Rc::new(LedgerKey::ContractData(LedgerKeyContractData {
+        contract: ScAddress::Contract(id),
+        key: ScVal::I32(0),
+        durability: ContractDataDurability::Temporary,
+    }));
+    superset.record_access(&extra, AccessType::ReadWrite, budget)?;
+    assert!(host.footprint_covers(&superset)?);
+
+    // Declaring a written key read-only is not enough.
+    let read_only: Vec<_> = recorded
+        .0
+        .map
+        .iter()
+        .map(|(k, _)| (Rc::clone(k), AccessType::ReadOnly))
+        .collect();
+    let read_only = Footprint(MeteredOrdMap::from_map(read_only, budget)?);
+    assert!(!host.footprint_covers(&read_only)?);
+
+    // Neither is leaving out a key that was accessed.
+    let mut missing = recorded.0.map.clone();
+    missing.pop();
+    let missing = Footprint(MeteredOrdMap::from_map(missing, budget)?);
+    assert!(!host.footprint_covers(&missing)?);
+    assert!(!host.footprint_covers(&Footprint::default())?);
+    Ok(())
+}
@@ -210,3 +210,22 @@ fn invalid_symbol_conversions() {
         );
     }
 }
+
+#[test]
+fn symbol_to_string() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let small = Symbol::try_from_val(&host, &"create")?;
+    assert_eq!(host.symbol_to_string(small.to_val())?, "create");
+
+    // Symbols too long to be small are objects, and are read back too.
+    let long = "a_symbol_too_long_to_be_small";
+    let obj = Symbol::try_from_val(&host, &long)?;
+    assert!(SymbolSmall::try_from(obj).is_err());
+    assert_eq!(host.symbol_to_string(obj.to_val())?, long);
+
+    assert!(HostError::result_matches_err(
+        host.symbol_to_string(Val::from_u32(1).to_val()),
+        (ScErrorType::Value, ScErrorCode::UnexpectedType)
+    ));
+    Ok(())
+}
@@ -1,6 +1,9 @@
-use soroban_env_common::TryIntoVal;
+use soroban_builtin_sdk_macros::contractimpl;
+use soroban_env_common::{TryIntoVal, VecObject};
 
-use crate::{Env, Host, HostError, Val};
+use crate::{
+    builtin_contracts::BuiltinContract, Env, Error, ErrorHandler, Host, HostError, Symbol, Val,
+};
 
 #[test]
 fn tuple_conversions() -> Result<(), HostError> {
@@ -33,3 +36,48 @@ fn tuple_array_conversions() -> Result<(), HostError> {
 
     Ok(())
 }
+
+const DIV_BY_ZERO: u32 = 1;
+
+struct DivRemContract;
+
+#[contractimpl]
+impl DivRemContract {
+    pub fn div_rem(e: &Host, a: u32, b: u32) -> Result<(u32, u32), HostError> {
+        if b == 0 {
+            return Err(e.error(
+                Error::from_contract_error(DIV_BY_ZERO),
+                "division by zero",
+                &[],
+            ));
+        }
+        Ok((a / b, a % b))
+    }
+}
+
+#[test]
+fn native_contract_tuple_return() -> Result<(), HostError> {
+    let host = Host::test_host();
+
+    let res = DivRemContract.call(
+        &Symbol::try_from_small_str("div_rem")?,
+        &host,
+        &[17u32.into(), 5u32.into()],
+    )?;
+    let vec: VecObject = res.try_into()?;
+    assert_eq!(u32::from(host.vec_len(vec)?), 2);
+    let (q, r): (u32, u32) = res.try_into_val(&host)?;
+    assert_eq!((q, r), (3, 2));
+
+    let res = DivRemContract.call(
+        &Symbol::try_from_small_str("div_rem")?,
+        &host,
+        &[17u32.into(), 0u32.into()],
+    );
+    assert!(HostError::result_matches_err(
+        res,
+        Error::from_contract_error(DIV_BY_ZERO)
+    ));
+
+    Ok(())
+}
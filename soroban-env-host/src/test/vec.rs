@@ -1,8 +1,10 @@
+#[cfg(feature = "next")]
+use crate::xdr::ScValType;
 use crate::{
     testutils::wasm,
     xdr::{ContractCostType, ScErrorCode, ScErrorType, ScVal},
-    Compare, Env, ErrorHandler, Host, HostError, Object, Symbol, Tag, TryFromVal, U32Val, Val,
-    VecObject,
+    Compare, Env, EnvBase, ErrorHandler, Host, HostError, Object, Symbol, Tag, TryFromVal, U32Val,
+    Val, VecObject,
 };
 use core::cmp::Ordering;
 use more_asserts::assert_ge;
@@ -301,7 +303,6 @@ fn vec_binary_search() -> Result<(), HostError> {
 
 #[test]
 fn vec_build_bad_element_integrity() -> Result<(), HostError> {
-    use crate::EnvBase;
     let host = observe_host!(Host::test_host());
     let obj = host.test_vec_obj::<u32>(&[1, 2, 3])?;
     let i = U32Val::from(1);
@@ -415,7 +416,6 @@ fn large_vec_exceeds_budget() {
 
 #[test]
 fn instantiate_oversized_vec_from_slice() -> Result<(), HostError> {
-    use crate::EnvBase;
     let host = observe_host!(Host::test_host());
 
     let buf = vec![0; 7_000_000];
@@ -491,3 +491,482 @@ fn instantiate_oversized_vec_from_linear_memory() -> Result<(), HostError> {
 
     Ok(())
 }
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_is_homogeneous() -> Result<(), HostError> {
+    let host = Host::test_host();
+
+    let obj = host.test_vec_obj::<u32>(&[1, 2, 3])?;
+    assert!(bool::try_from(host.vec_is_homogeneous(obj)?)?);
+    assert_eq!(
+        host.vec_element_tags(obj)?,
+        vec![Tag::U32Val, Tag::U32Val, Tag::U32Val]
+    );
+
+    let sym = Symbol::try_from_val(&host, &"hi")?;
+    let obj = host.vec_new_from_slice(&[1u32.into(), sym.to_val(), true.into()])?;
+    assert!(!bool::try_from(host.vec_is_homogeneous(obj)?)?);
+    assert_eq!(
+        host.vec_element_tags(obj)?,
+        vec![Tag::U32Val, Tag::SymbolSmall, Tag::True]
+    );
+
+    // Small and object forms of the same type count as the same type.
+    let small = Val::try_from_val(&host, &1u64)?;
+    let big = Val::try_from_val(&host, &u64::MAX)?;
+    assert_eq!(small.get_tag(), Tag::U64Small);
+    assert_eq!(big.get_tag(), Tag::U64Object);
+    let obj = host.vec_new_from_slice(&[small, big])?;
+    assert!(bool::try_from(host.vec_is_homogeneous(obj)?)?);
+
+    let obj = host.vec_new()?;
+    assert!(bool::try_from(host.vec_is_homogeneous(obj)?)?);
+    assert!(host.vec_element_tags(obj)?.is_empty());
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_to_indexed_map() -> Result<(), HostError> {
+    let host = Host::test_host();
+
+    let obj = host.test_vec_obj::<u32>(&[10, 20, 30])?;
+    let map = host.vec_to_indexed_map(obj)?;
+    let mut expected = host.map_new()?;
+    for (i, x) in [10u32, 20, 30].iter().enumerate() {
+        expected = host.map_put(expected, (i as u32).into(), (*x).into())?;
+    }
+    assert_eq!(host.obj_cmp(map.into(), expected.into())?, 0);
+
+    let empty = host.vec_to_indexed_map(host.vec_new()?)?;
+    assert_eq!(u32::from(host.map_len(empty)?), 0);
+    assert_eq!(host.obj_cmp(empty.into(), host.map_new()?.into())?, 0);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_partition_by_contract_predicate() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(&wasm::wasm_module_with_vec_partition());
+    let partition = |func: &str| -> Result<Val, HostError> {
+        let v = host.test_vec_obj::<u32>(&[1, 2, 3, 4])?;
+        let func = Symbol::try_from_small_str(func)?;
+        let args = host.vec_new_from_slice(&[v.to_val(), func.to_val()])?;
+        host.call(contract, Symbol::try_from_small_str("partition")?, args)
+    };
+
+    let res = partition("is_even")?;
+    let expected = host.vec_new_from_slice(&[
+        host.test_vec_val::<u32>(&[2, 4])?,
+        host.test_vec_val::<u32>(&[1, 3])?,
+    ])?;
+    assert_eq!(host.obj_cmp(res, expected.to_val())?, 0);
+
+    // A callback that doesn't return a bool is an error.
+    assert!(HostError::result_matches_err(
+        partition("ident"),
+        (ScErrorType::Value, ScErrorCode::UnexpectedType)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_partition_callback_writing_storage_does_not_affect_input() -> Result<(), HostError> {
+    use crate::{
+        xdr::{Hash, ScAddress},
+        ContractFunctionSet, StorageType,
+    };
+    use std::rc::Rc;
+
+    // `part` stores its argument under `v` and partitions the stored vec
+    // with `pred`, which overwrites `v` with an empty vec and counts its
+    // calls under `n` each time it runs. `stored` returns `[v, n]`.
+    struct PartitionStored;
+    impl ContractFunctionSet for PartitionStored {
+        fn call(&self, func: &Symbol, host: &Host, args: &[Val]) -> Option<Val> {
+            let sym = |s: &str| host.symbol_new_from_slice(s.as_bytes()).unwrap();
+            let is = |s: &str| {
+                host.compare(&sym(s).to_val(), &func.to_val())
+                    .unwrap()
+                    .is_eq()
+            };
+            let (v, n) = (sym("v").to_val(), sym("n").to_val());
+            let val = if is("__constructor") {
+                ().into()
+            } else if is("part") {
+                host.put_contract_data(v, args[0], StorageType::Persistent)
+                    .unwrap();
+                let stored = host.get_contract_data(v, StorageType::Persistent).unwrap();
+                host.vec_partition(stored.try_into().unwrap(), sym("pred"))
+                    .unwrap()
+                    .to_val()
+            } else if is("pred") {
+                let count = if host
+                    .has_contract_data(n, StorageType::Persistent)
+                    .unwrap()
+                    .into()
+                {
+                    u32::from(
+                        U32Val::try_from(
+                            host.get_contract_data(n, StorageType::Persistent).unwrap(),
+                        )
+                        .unwrap(),
+                    )
+                } else {
+                    0
+                };
+                host.put_contract_data(
+                    n,
+                    U32Val::from(count + 1).to_val(),
+                    StorageType::Persistent,
+                )
+                .unwrap();
+                host.put_contract_data(
+                    v,
+                    host.vec_new().unwrap().to_val(),
+                    StorageType::Persistent,
+                )
+                .unwrap();
+                (u32::from(U32Val::try_from(args[0]).unwrap()) % 2 == 0).into()
+            } else if is("stored") {
+                host.vec_new_from_slice(&[
+                    host.get_contract_data(v, StorageType::Persistent).unwrap(),
+                    host.get_contract_data(n, StorageType::Persistent).unwrap(),
+                ])
+                .unwrap()
+                .to_val()
+            } else {
+                return None;
+            };
+            Some(val)
+        }
+    }
+
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.add_host_object(ScAddress::Contract(Hash([0; 32])))?;
+    host.register_test_contract(contract, Rc::new(PartitionStored))?;
+
+    let input = host.test_vec_val::<u32>(&[1, 2, 3, 4])?;
+    let args = host.vec_new_from_slice(&[input])?;
+    let res = host.call(contract, Symbol::try_from_small_str("part")?, args)?;
+    let expected = host.vec_new_from_slice(&[
+        host.test_vec_val::<u32>(&[2, 4])?,
+        host.test_vec_val::<u32>(&[1, 3])?,
+    ])?;
+    assert_eq!(host.obj_cmp(res, expected.to_val())?, 0);
+
+    // Every callback ran and overwrote the stored vec, but the partition
+    // saw the vec as it was when `vec_partition` was called.
+    let stored = host.call(
+        contract,
+        Symbol::try_from_small_str("stored")?,
+        host.vec_new()?,
+    )?;
+    let expected = host.vec_new_from_slice(&[host.vec_new()?.to_val(), 4u32.into()])?;
+    assert_eq!(host.obj_cmp(stored, expected.to_val())?, 0);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_flatten() -> Result<(), HostError> {
+    let host = Host::test_host();
+
+    let outer = host.vec_new_from_slice(&[
+        host.test_vec_val::<u32>(&[1, 2])?,
+        host.test_vec_val::<u32>(&[3])?,
+        host.test_vec_val::<u32>(&[])?,
+    ])?;
+    let flat = host.vec_flatten(outer)?;
+    let expected = host.test_vec_obj::<u32>(&[1, 2, 3])?;
+    assert_eq!(host.obj_cmp(flat.into(), expected.into())?, 0);
+
+    let flat = host.vec_flatten(host.vec_new()?)?;
+    assert_eq!(u32::from(host.vec_len(flat)?), 0);
+
+    let outer = host.vec_new_from_slice(&[host.test_vec_val::<u32>(&[1])?, 2u32.into()])?;
+    assert!(HostError::result_matches_err(
+        host.vec_flatten(outer),
+        (ScErrorType::Value, ScErrorCode::UnexpectedType)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_sort_by_contract_key_function() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(&wasm::wasm_module_with_vec_sort_by());
+    let records = |pairs: &[[u32; 2]]| -> Result<VecObject, HostError> {
+        let mut v = host.vec_new()?;
+        for pair in pairs {
+            v = host.vec_push_back(v, host.test_vec_val::<u32>(pair)?)?;
+        }
+        Ok(v)
+    };
+
+    // Records with equal keys keep their relative order.
+    let v = records(&[[3, 0], [1, 1], [2, 2], [1, 3]])?;
+    let args =
+        host.vec_new_from_slice(&[v.to_val(), Symbol::try_from_small_str("first")?.to_val()])?;
+    let res = host.call(contract, Symbol::try_from_small_str("sort")?, args)?;
+    let expected = records(&[[1, 1], [1, 3], [2, 2], [3, 0]])?;
+    assert_eq!(host.obj_cmp(res, expected.to_val())?, 0);
+    // The input is unchanged.
+    let original = records(&[[3, 0], [1, 1], [2, 2], [1, 3]])?;
+    assert_eq!(host.obj_cmp(v.to_val(), original.to_val())?, 0);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_put_typed() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let v = host.test_vec_obj::<u32>(&[1, 2, 3])?;
+    let u32_type = U32Val::from(ScValType::U32 as u32);
+
+    // A value of the expected type is put like `vec_put`.
+    let res = host.vec_put_typed(v, U32Val::from(1), U32Val::from(9).to_val(), u32_type)?;
+    let expected = host.test_vec_obj::<u32>(&[1, 9, 3])?;
+    assert_eq!(host.obj_cmp(res.to_val(), expected.to_val())?, 0);
+
+    // A value of any other type is rejected.
+    assert!(HostError::result_matches_err(
+        host.vec_put_typed(v, U32Val::from(1), true.into(), u32_type),
+        (ScErrorType::Value, ScErrorCode::UnexpectedType)
+    ));
+
+    // Types are compared at the `ScValType` level, so both forms of a u64
+    // (and both bools) match their type, whichever tag they have.
+    let u64_type = U32Val::from(ScValType::U64 as u32);
+    let small = Val::try_from_val(&host, &1_u64)?;
+    assert_eq!(small.get_tag(), Tag::U64Small);
+    let big = Val::try_from_val(&host, &u64::MAX)?;
+    assert_eq!(big.get_tag(), Tag::U64Object);
+    let w = host.vec_new_from_slice(&[small, small])?;
+    let w = host.vec_put_typed(w, U32Val::from(0), big, u64_type)?;
+    host.vec_put_typed(w, U32Val::from(1), small, u64_type)?;
+    let bool_type = U32Val::from(ScValType::Bool as u32);
+    let b = host.vec_new_from_slice(&[true.into()])?;
+    host.vec_put_typed(b, U32Val::from(0), false.into(), bool_type)?;
+
+    // The index must be in bounds.
+    let code = (ScErrorType::Object, ScErrorCode::IndexBounds);
+    assert!(HostError::result_matches_err(
+        host.vec_put_typed(v, U32Val::from(3), U32Val::from(9).to_val(), u32_type),
+        code
+    ));
+    assert!(HostError::result_matches_err(
+        host.vec_put_typed(
+            v,
+            U32Val::from(u32::MAX),
+            U32Val::from(9).to_val(),
+            u32_type
+        ),
+        code
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_group_by_contract_key_function() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(&wasm::wasm_module_with_vec_group_by());
+    let group = |v: VecObject| -> Result<Val, HostError> {
+        let func = Symbol::try_from_small_str("is_even")?;
+        let args = host.vec_new_from_slice(&[v.to_val(), func.to_val()])?;
+        host.call(contract, Symbol::try_from_small_str("group")?, args)
+    };
+
+    let res = group(host.test_vec_obj::<u32>(&[1, 2, 3, 4])?)?;
+    let expected = host.map_new()?;
+    let expected = host.map_put(expected, false.into(), host.test_vec_val::<u32>(&[1, 3])?)?;
+    let expected = host.map_put(expected, true.into(), host.test_vec_val::<u32>(&[2, 4])?)?;
+    assert_eq!(host.obj_cmp(res, expected.to_val())?, 0);
+
+    // Only keys that occur get an entry, and an empty vector has none.
+    let res = group(host.test_vec_obj::<u32>(&[4, 2])?)?;
+    let expected = host.map_new()?;
+    let expected = host.map_put(expected, true.into(), host.test_vec_val::<u32>(&[4, 2])?)?;
+    assert_eq!(host.obj_cmp(res, expected.to_val())?, 0);
+    let res = group(host.vec_new()?)?;
+    assert_eq!(host.obj_cmp(res, host.map_new()?.to_val())?, 0);
+    Ok(())
+}
+
+#[test]
+fn vec_get_opt() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let v = host.test_vec_obj::<u32>(&[1, 2, 3])?;
+    let got = host.vec_get_opt(v.into(), 2)?.expect("index is in range");
+    assert_eq!(u32::try_from_val(&host, &got)?, 3);
+
+    // An out-of-range index is not an error.
+    assert!(host.vec_get_opt(v.into(), 3)?.is_none());
+    assert!(host.vec_get_opt(v.into(), u32::MAX)?.is_none());
+
+    // A non-vector object still is.
+    let m = host.map_new()?;
+    assert!(HostError::result_matches_err(
+        host.vec_get_opt(m.into(), 0),
+        (ScErrorType::Value, ScErrorCode::UnexpectedType)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_difference() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let diff = |a: &[u32], b: &[u32], expected: &[u32]| -> Result<(), HostError> {
+        let a = host.test_vec_obj::<u32>(a)?;
+        let b = host.test_vec_obj::<u32>(b)?;
+        let expected = host.test_vec_obj::<u32>(expected)?;
+        let res = host.vec_difference(a, b)?;
+        assert_eq!(host.obj_cmp(res.to_val(), expected.to_val())?, 0);
+        Ok(())
+    };
+    // Disjoint vectors leave `a` as it is.
+    diff(&[3, 1, 2], &[4, 5], &[3, 1, 2])?;
+    // Overlap is removed, keeping the order and repeats of what is left.
+    diff(&[1, 2, 3, 2, 4, 1], &[1, 3], &[2, 2, 4])?;
+    diff(&[1, 2], &[2, 1], &[])?;
+    // An empty `a` gives an empty result.
+    diff(&[], &[1, 2], &[])?;
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_any_all_by_contract_predicate() -> Result<(), HostError> {
+    let host = Host::test_host_with_recording_footprint();
+    let contract = host.register_test_contract_wasm(&wasm::wasm_module_with_vec_any_all());
+    let test = |func: &str, elts: &[u32]| -> Result<bool, HostError> {
+        let v = host.test_vec_obj::<u32>(elts)?;
+        let pred = Symbol::try_from_small_str("is_even")?;
+        let args = host.vec_new_from_slice(&[v.to_val(), pred.to_val()])?;
+        let res = host.call(contract, Symbol::try_from_small_str(func)?, args)?;
+        Ok(bool::try_from(res)?)
+    };
+
+    assert!(test("all", &[2, 4, 6])?);
+    assert!(test("any", &[2, 4, 6])?);
+    assert!(!test("all", &[1, 3])?);
+    assert!(!test("any", &[1, 3])?);
+    assert!(!test("all", &[2, 3])?);
+    assert!(test("any", &[1, 2])?);
+
+    // The empty vector is vacuously all-even, and has no even element.
+    assert!(test("all", &[])?);
+    assert!(!test("any", &[])?);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_chunks() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let v = host.test_vec_obj::<u32>(&[1, 2, 3, 4, 5])?;
+
+    let res = host.vec_chunks(v, U32Val::from(2))?;
+    let expected = host.vec_new_from_slice(&[
+        host.test_vec_val::<u32>(&[1, 2])?,
+        host.test_vec_val::<u32>(&[3, 4])?,
+        host.test_vec_val::<u32>(&[5])?,
+    ])?;
+    assert_eq!(host.obj_cmp(res.to_val(), expected.to_val())?, 0);
+
+    // A size at least the length gives a single chunk.
+    let res = host.vec_chunks(v, U32Val::from(10))?;
+    let expected = host.vec_new_from_slice(&[v.to_val()])?;
+    assert_eq!(host.obj_cmp(res.to_val(), expected.to_val())?, 0);
+
+    // An empty vector has no chunks.
+    let res = host.vec_chunks(host.vec_new()?, U32Val::from(2))?;
+    assert_eq!(u32::from(host.vec_len(res)?), 0);
+
+    assert!(HostError::result_matches_err(
+        host.vec_chunks(v, U32Val::from(0)),
+        (ScErrorType::Value, ScErrorCode::InvalidInput)
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_is_sorted() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let is_sorted = |elts: &[u32]| -> Result<bool, HostError> {
+        let v = host.test_vec_obj::<u32>(elts)?;
+        Ok(host.vec_is_sorted(v)?.into())
+    };
+    assert!(is_sorted(&[1, 2, 2, 5, 9])?);
+    assert!(!is_sorted(&[1, 3, 2, 4])?);
+    assert!(!is_sorted(&[2, 1])?);
+    assert!(is_sorted(&[])?);
+    assert!(is_sorted(&[7])?);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_all_bytes_of_len() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let all_of_len = |elts: &[Val], len: u32| -> Result<bool, HostError> {
+        let v = host.vec_new_from_slice(elts)?;
+        Ok(host.vec_all_bytes_of_len(v, len.into())?.into())
+    };
+    let hash =
+        |b: u8| -> Result<Val, HostError> { Ok(host.bytes_new_from_slice(&[b; 32])?.to_val()) };
+    let hashes = [hash(1)?, hash(2)?, hash(3)?];
+    assert!(all_of_len(&hashes, 32)?);
+    assert!(!all_of_len(&hashes, 31)?);
+    assert!(all_of_len(&[], 32)?);
+
+    // One short bytes object, or one element that isn't bytes at all, is
+    // enough to fail.
+    let short = host.bytes_new_from_slice(&[0; 31])?.to_val();
+    assert!(!all_of_len(&[hashes[0], short, hashes[1]], 32)?);
+    let not_bytes = host.string_new_from_slice(&[0; 32])?.to_val();
+    assert!(!all_of_len(&[hashes[0], not_bytes], 32)?);
+    assert!(!all_of_len(&[32_u32.into()], 32)?);
+    Ok(())
+}
+
+#[cfg(feature = "next")]
+#[test]
+fn vec_heap_push_and_pop() -> Result<(), HostError> {
+    let host = Host::test_host();
+    let mut heap = host.vec_new()?;
+    for x in [5_u32, 1, 8, 3, 9, 1, 7] {
+        heap = host.vec_heap_push(heap, x.into())?;
+    }
+    // The root of a min-heap is its smallest element.
+    assert_eq!(u32::from(U32Val::try_from(host.vec_front(heap)?)?), 1);
+
+    let mut popped = vec![];
+    loop {
+        let res = host.vec_heap_pop(heap)?;
+        let x = host.vec_get(res, 1_u32.into())?;
+        if x.is_void() {
+            break;
+        }
+        heap = VecObject::try_from(host.vec_get(res, 0_u32.into())?)?;
+        popped.push(u32::from(U32Val::try_from(x)?));
+    }
+    assert_eq!(popped, [1, 1, 3, 5, 7, 8, 9]);
+
+    // Popping an empty heap returns it unchanged, along with void.
+    let empty = host.vec_new()?;
+    let res = host.vec_heap_pop(empty)?;
+    assert_eq!(
+        host.obj_cmp(host.vec_get(res, 0_u32.into())?, empty.to_val())?,
+        0
+    );
+    assert!(host.vec_get(res, 1_u32.into())?.is_void());
+    Ok(())
+}
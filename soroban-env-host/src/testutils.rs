@@ -145,6 +145,20 @@ impl SnapshotSource for MockSnapshotSource {
     }
 }
 
+/// The host state captured by [`Host::checkpoint`], to be restored by
+/// [`Host::rollback`].
+pub struct HostCheckpoint {
+    objects: usize,
+    context_depth: usize,
+    storage: Storage,
+    budget: crate::budget::BudgetImpl,
+    events: crate::events::InternalEventsBuffer,
+    authorization_manager: crate::auth::AuthorizationManager,
+    interned_objects: Option<BTreeMap<[u8; 32], crate::Object>>,
+    config_entries: crate::host::ConfigEntries,
+    staged: Option<Vec<crate::host::frame::StagedContractData>>,
+}
+
 #[cfg(test)]
 pub(crate) fn interface_meta_with_custom_versions(proto: u32, pre: u32) -> Vec<u8> {
     use crate::xdr::{Limited, Limits, ScEnvMetaEntry, ScEnvMetaEntryInterfaceVersion, WriteXdr};
@@ -278,6 +292,243 @@ impl Host {
         Ok(val.try_into()?)
     }
 
+    /// Serializes the host's object table, in handle order, as the XDR of an
+    /// `ScVal::Vec` with one entry per object. Containers are stored
+    /// shallowly: each element of a `Vec` or key/value of a `Map` is recorded
+    /// as the raw payload of its `Val` in an `ScVal::U64`, so references to
+    /// other objects are kept as handles. Every other object is stored as its
+    /// own `ScVal`.
+    ///
+    /// Only meaningful outside of any contract invocation, since objects
+    /// created inside a VM frame are referenced through relative handles.
+    pub fn snapshot_objects(&self) -> Result<Vec<u8>, HostError> {
+        use crate::xdr::{
+            int128_helpers, Int128Parts, Int256Parts, ScMap, ScMapEntry, UInt128Parts,
+            UInt256Parts, WriteXdr,
+        };
+        use crate::{
+            host_object::HostObject,
+            num::{i256_into_pieces, u256_into_pieces},
+        };
+        let payload = |v: &Val| ScVal::U64(v.get_payload());
+        let mut entries = Vec::<ScVal>::new();
+        for obj in self.try_borrow_objects()?.iter() {
+            let entry = match obj {
+                HostObject::Vec(v) => {
+                    let elts: Vec<ScVal> = v.iter().map(payload).collect();
+                    ScVal::Vec(Some(self.map_err(elts.try_into())?))
+                }
+                HostObject::Map(m) => {
+                    let pairs: Vec<ScMapEntry> = m
+                        .map
+                        .iter()
+                        .map(|(k, v)| ScMapEntry {
+                            key: payload(k),
+                            val: payload(v),
+                        })
+                        .collect();
+                    ScVal::Map(Some(ScMap(self.map_err(pairs.try_into())?)))
+                }
+                HostObject::U64(u) => ScVal::U64(*u),
+                HostObject::I64(i) => ScVal::I64(*i),
+                HostObject::TimePoint(tp) => ScVal::Timepoint(tp.clone()),
+                HostObject::Duration(d) => ScVal::Duration(d.clone()),
+                HostObject::U128(u) => ScVal::U128(UInt128Parts {
+                    hi: int128_helpers::u128_hi(*u),
+                    lo: int128_helpers::u128_lo(*u),
+                }),
+                HostObject::I128(i) => ScVal::I128(Int128Parts {
+                    hi: int128_helpers::i128_hi(*i),
+                    lo: int128_helpers::i128_lo(*i),
+                }),
+                HostObject::U256(u) => {
+                    let (hi_hi, hi_lo, lo_hi, lo_lo) = u256_into_pieces(*u);
+                    ScVal::U256(UInt256Parts {
+                        hi_hi,
+                        hi_lo,
+                        lo_hi,
+                        lo_lo,
+                    })
+                }
+                HostObject::I256(i) => {
+                    let (hi_hi, hi_lo, lo_hi, lo_lo) = i256_into_pieces(*i);
+                    ScVal::I256(Int256Parts {
+                        hi_hi,
+                        hi_lo,
+                        lo_hi,
+                        lo_lo,
+                    })
+                }
+                HostObject::Bytes(b) => ScVal::Bytes(b.clone()),
+                HostObject::String(s) => ScVal::String(s.clone()),
+                HostObject::Symbol(s) => ScVal::Symbol(s.clone()),
+                HostObject::Address(a) => ScVal::Address(a.clone()),
+            };
+            entries.push(entry);
+        }
+        let table = ScVal::Vec(Some(self.map_err(entries.try_into())?));
+        self.map_err(table.to_xdr(crate::DEFAULT_XDR_RW_LIMITS))
+    }
+
+    /// Replaces the host's object table with one previously produced by
+    /// [`Host::snapshot_objects`], so that every handle that was live at
+    /// snapshot time refers to the same value again. Objects created after
+    /// the snapshot are discarded.
+    pub fn restore_objects(&self, bytes: &[u8]) -> Result<(), HostError> {
+        use crate::xdr::{int128_helpers, ReadXdr, ScErrorCode, ScErrorType};
+        use crate::{
+            host_object::{HostMap, HostObject, HostVec},
+            num::{i256_from_pieces, u256_from_pieces},
+        };
+        let table = self.map_err(ScVal::from_xdr(bytes, crate::DEFAULT_XDR_RW_LIMITS))?;
+        let ScVal::Vec(Some(entries)) = table else {
+            return Err(self.err(
+                ScErrorType::Value,
+                ScErrorCode::InvalidInput,
+                "object snapshot is not a vector",
+                &[],
+            ));
+        };
+        let unpayload = |v: &ScVal| match v {
+            ScVal::U64(p) => Ok(Val::from_payload(*p)),
+            _ => Err(self.err(
+                ScErrorType::Value,
+                ScErrorCode::InvalidInput,
+                "malformed container element in object snapshot",
+                &[],
+            )),
+        };
+        // Objects are pushed back in handle order, so each map's keys only
+        // refer to objects that have already been restored when the map is
+        // rebuilt. That needs the new table to be live while it's built, so
+        // the old one is set aside and put back if the snapshot turns out to
+        // be malformed.
+        let old_objects = std::mem::take(&mut *self.try_borrow_objects_mut()?);
+        let rebuild = || -> Result<(), HostError> {
+            for entry in entries.iter() {
+                let obj = match entry {
+                    ScVal::Vec(Some(v)) => HostObject::Vec(HostVec::from_vec(
+                        v.iter().map(unpayload).collect::<Result<_, _>>()?,
+                    )?),
+                    ScVal::Map(Some(m)) => HostObject::Map(HostMap::from_map(
+                        m.iter()
+                            .map(|e| Ok((unpayload(&e.key)?, unpayload(&e.val)?)))
+                            .collect::<Result<_, HostError>>()?,
+                        self,
+                    )?),
+                    ScVal::U64(u) => HostObject::U64(*u),
+                    ScVal::I64(i) => HostObject::I64(*i),
+                    ScVal::Timepoint(tp) => HostObject::TimePoint(tp.clone()),
+                    ScVal::Duration(d) => HostObject::Duration(d.clone()),
+                    ScVal::U128(u) => {
+                        HostObject::U128(int128_helpers::u128_from_pieces(u.hi, u.lo))
+                    }
+                    ScVal::I128(i) => {
+                        HostObject::I128(int128_helpers::i128_from_pieces(i.hi, i.lo))
+                    }
+                    ScVal::U256(u) => {
+                        HostObject::U256(u256_from_pieces(u.hi_hi, u.hi_lo, u.lo_hi, u.lo_lo))
+                    }
+                    ScVal::I256(i) => {
+                        HostObject::I256(i256_from_pieces(i.hi_hi, i.hi_lo, i.lo_hi, i.lo_lo))
+                    }
+                    ScVal::Bytes(b) => HostObject::Bytes(b.clone()),
+                    ScVal::String(s) => HostObject::String(s.clone()),
+                    ScVal::Symbol(s) => HostObject::Symbol(s.clone()),
+                    ScVal::Address(a) => HostObject::Address(a.clone()),
+                    _ => {
+                        return Err(self.err(
+                            ScErrorType::Value,
+                            ScErrorCode::InvalidInput,
+                            "unexpected entry in object snapshot",
+                            &[],
+                        ))
+                    }
+                };
+                self.try_borrow_objects_mut()?.push(obj);
+            }
+            Ok(())
+        };
+        if let Err(e) = rebuild() {
+            *self.try_borrow_objects_mut()? = old_objects;
+            return Err(e);
+        }
+        // Interned handles refer to the old table.
+        if let Some(cache) = self.try_borrow_interned_objects_mut()?.as_mut() {
+            cache.clear();
+        }
+        Ok(())
+    }
+
+    /// Captures the host's mutable execution state, so that
+    /// [`Host::rollback`] can later return the host to it: the object table,
+    /// storage (including the footprint), budget, events, authorization
+    /// state, the data that refers to objects, such as interned and
+    /// configuration values, and the writes staged in the running contract's
+    /// frame. The rest of the call stack is not copied: a checkpoint can only
+    /// be rolled back to at the call depth it was taken at.
+    ///
+    /// This is unmetered, and the checkpoint itself doesn't count against
+    /// the budget it captures.
+    pub fn checkpoint(&self) -> Result<HostCheckpoint, HostError> {
+        use crate::host::error::TryBorrowOrErr;
+        Ok(HostCheckpoint {
+            objects: self.try_borrow_objects()?.len(),
+            context_depth: self.try_borrow_context_stack()?.len(),
+            storage: self.try_borrow_storage()?.clone(),
+            budget: self.as_budget().0.try_borrow_or_err()?.clone(),
+            events: self.try_borrow_events()?.clone(),
+            authorization_manager: self.try_borrow_authorization_manager()?.clone(),
+            interned_objects: self.try_borrow_interned_objects()?.clone(),
+            config_entries: self.try_borrow_config_entries()?.clone(),
+            staged: self
+                .try_borrow_context_stack()?
+                .last()
+                .map(|ctx| ctx.staged.clone()),
+        })
+    }
+
+    /// Returns the host to the state captured by [`Host::checkpoint`].
+    /// Objects created since then are discarded, so their handles no longer
+    /// resolve. Fails, changing nothing, if the call depth differs from the
+    /// checkpoint's or objects it refers to have already been discarded by
+    /// an earlier rollback.
+    pub fn rollback(&self, cp: HostCheckpoint) -> Result<(), HostError> {
+        use crate::host::error::TryBorrowOrErr;
+        use crate::xdr::{ScErrorCode, ScErrorType};
+        if self.try_borrow_context_stack()?.len() != cp.context_depth {
+            return Err(self.err(
+                ScErrorType::Context,
+                ScErrorCode::InvalidAction,
+                "checkpoint taken at a different call depth",
+                &[],
+            ));
+        }
+        if self.try_borrow_objects()?.len() < cp.objects {
+            return Err(self.err(
+                ScErrorType::Context,
+                ScErrorCode::InvalidAction,
+                "checkpoint refers to objects that were rolled back",
+                &[],
+            ));
+        }
+        // Objects are never modified once created, so dropping the newer
+        // ones restores the table.
+        self.try_borrow_objects_mut()?.truncate(cp.objects);
+        *self.try_borrow_storage_mut()? = cp.storage;
+        *self.as_budget().0.try_borrow_mut_or_err()? = cp.budget;
+        *self.try_borrow_events_mut()? = cp.events;
+        *self.try_borrow_authorization_manager_mut()? = cp.authorization_manager;
+        *self.try_borrow_interned_objects_mut()? = cp.interned_objects;
+        *self.try_borrow_config_entries_mut()? = cp.config_entries;
+        if let (Some(ctx), Some(staged)) =
+            (self.try_borrow_context_stack_mut()?.last_mut(), cp.staged)
+        {
+            ctx.staged = staged;
+        }
+        Ok(())
+    }
+
     // Registers a contract with provided Wasm code and returns the registered
     // contract's address.
     // The contract address deterministically depends on the input account and
@@ -1143,6 +1394,289 @@ pub(crate) mod wasm {
         me.define_data_segment(0x1234, vec![0; 512]);
         me.finish()
     }
+
+    // A module exporting `stack`, which takes `n_callees` contract addresses.
+    // With no arguments it returns `get_call_stack()`; otherwise it calls
+    // `stack` on its first argument, passing along the remaining ones.
+    #[cfg(feature = "next")]
+    pub(crate) fn wasm_module_returning_call_stack(n_callees: u32) -> Vec<u8> {
+        let mut me = ModEmitter::default_with_test_protocol();
+        let mut fe = if n_callees == 0 {
+            // get_call_stack
+            let f0 = me.import_func("x", "9", Arity(0));
+            let mut fe = me.func(Arity(0), 0);
+            fe.call_func(f0);
+            fe
+        } else {
+            // call
+            let f0 = me.import_func("d", "_", Arity(3));
+            // vec_new
+            let f1 = me.import_func("v", "_", Arity(0));
+            // vec_push_back
+            let f2 = me.import_func("v", "6", Arity(2));
+            let mut fe = me.func(Arity(n_callees), 1);
+            let args = fe.locals[0].0;
+            fe.call_func(f1);
+            for i in 1..n_callees {
+                fe.push(Operand::Local(LocalRef(i)));
+                fe.call_func(f2);
+            }
+            fe.local_set(args);
+            fe.push(Operand::Local(LocalRef(0)));
+            fe.push(Symbol::try_from_small_str("stack").unwrap());
+            fe.push(Operand::Local(args));
+            fe.call_func(f0);
+            fe
+        };
+        fe.finish_and_export("stack").finish()
+    }
+
+    #[cfg(feature = "next")]
+    pub(crate) fn wasm_module_with_serialize_to_linear_memory(buf_len: u32) -> Vec<u8> {
+        let mut me = ModEmitter::default_with_test_protocol();
+        // serialize_to_linear_memory
+        let f0 = me.import_func("b", "n", Arity(3));
+        // bytes_new_from_linear_memory
+        let f1 = me.import_func("b", "3", Arity(2));
+        // the caller: serializes its argument into linear memory at 0, then
+        // reads the written bytes back out into a `Bytes` object.
+        let mut fe = me.func(Arity(1), 1);
+        let written = fe.locals[0].0;
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.push(U32Val::from(0).to_val());
+        fe.push(U32Val::from(buf_len).to_val());
+        fe.call_func(f0);
+        fe.local_set(written);
+        fe.push(U32Val::from(0).to_val());
+        fe.push(Operand::Local(written));
+        fe.call_func(f1);
+        fe.finish_and_export("test").finish()
+    }
+
+    // Adds an exported `is_even` function: whether a `U32Val` argument is
+    // even, i.e. the low bit of its upper 32 bits is clear, returned as `Bool`
+    // (payload 0 or 1). Used as a predicate or key function by callback tests.
+    #[cfg(feature = "next")]
+    fn emit_is_even_u32val(me: ModEmitter) -> ModEmitter {
+        let mut fe = me.func(Arity(1), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.i64_const(32);
+        fe.i64_shr_u();
+        fe.i64_const(1);
+        fe.i64_and();
+        fe.i64_const(1);
+        fe.i64_xor();
+        fe.finish_and_export("is_even")
+    }
+
+    #[cfg(feature = "next")]
+    pub(crate) fn wasm_module_with_vec_partition() -> Vec<u8> {
+        let mut me = ModEmitter::default_with_test_protocol();
+        // vec_partition
+        let f0 = me.import_func("v", "k", Arity(2));
+        let mut me = emit_is_even_u32val(me);
+        // a non-predicate, returning its argument unchanged.
+        let mut fe = me.func(Arity(1), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        let mut me = fe.finish_and_export("ident");
+        // the caller: partitions its first argument by the function named in
+        // its second.
+        let mut fe = me.func(Arity(2), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.push(Operand::Local(LocalRef(1)));
+        fe.call_func(f0);
+        fe.finish_and_export("partition").finish()
+    }
+
+    #[cfg(feature = "next")]
+    pub(crate) fn wasm_module_with_vec_sort_by() -> Vec<u8> {
+        let mut me = ModEmitter::default_with_test_protocol();
+        // vec_sort_by
+        let f0 = me.import_func("v", "m", Arity(2));
+        // vec_get
+        let f1 = me.import_func("v", "1", Arity(2));
+        // a key function: the first element of a vector argument.
+        let mut fe = me.func(Arity(1), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.push(U32Val::from(0).to_val());
+        fe.call_func(f1);
+        let mut me = fe.finish_and_export("first");
+        // the caller: sorts its first argument by the function named in its
+        // second.
+        let mut fe = me.func(Arity(2), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.push(Operand::Local(LocalRef(1)));
+        fe.call_func(f0);
+        fe.finish_and_export("sort").finish()
+    }
+
+    #[cfg(feature = "next")]
+    pub(crate) fn wasm_module_with_vec_group_by() -> Vec<u8> {
+        let mut me = ModEmitter::default_with_test_protocol();
+        // vec_group_by
+        let f0 = me.import_func("v", "o", Arity(2));
+        let mut me = emit_is_even_u32val(me);
+        // the caller: groups its first argument by the function named in its
+        // second.
+        let mut fe = me.func(Arity(2), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.push(Operand::Local(LocalRef(1)));
+        fe.call_func(f0);
+        fe.finish_and_export("group").finish()
+    }
+
+    pub(crate) fn wasm_module_with_five_host_calls() -> Vec<u8> {
+        let mut me = ModEmitter::default_with_test_protocol();
+        // vec_new
+        let f0 = me.import_func("v", "_", Arity(0));
+        // vec_push_back
+        let f1 = me.import_func("v", "6", Arity(2));
+        // vec_len
+        let f2 = me.import_func("v", "3", Arity(1));
+        // builds a 3-element vector and returns its length.
+        let mut fe = me.func(Arity(0), 0);
+        fe.call_func(f0);
+        for i in 0..3_u32 {
+            fe.push(U32Val::from(i).to_val());
+            fe.call_func(f1);
+        }
+        fe.call_func(f2);
+        fe.finish_and_export("test").finish()
+    }
+
+    #[cfg(feature = "next")]
+    pub(crate) fn wasm_module_calling_get_config() -> Vec<u8> {
+        let mut me = ModEmitter::default_with_test_protocol();
+        // get_config
+        let f0 = me.import_func("x", "k", Arity(1));
+        let mut fe = me.func(Arity(1), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.call_func(f0);
+        fe.finish_and_export("get").finish()
+    }
+
+    #[cfg(feature = "next")]
+    pub(crate) fn wasm_module_calling_contracts_share_code() -> Vec<u8> {
+        let mut me = ModEmitter::default_with_test_protocol();
+        // contracts_share_code
+        let f0 = me.import_func("l", "l", Arity(2));
+        let mut fe = me.func(Arity(2), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.push(Operand::Local(LocalRef(1)));
+        fe.call_func(f0);
+        fe.finish_and_export("share").finish()
+    }
+
+    #[cfg(feature = "next")]
+    pub(crate) fn wasm_module_with_vec_any_all() -> Vec<u8> {
+        let mut me = ModEmitter::default_with_test_protocol();
+        // vec_any
+        let f0 = me.import_func("v", "q", Arity(2));
+        // vec_all
+        let f1 = me.import_func("v", "r", Arity(2));
+        let mut me = emit_is_even_u32val(me);
+        // the callers: test their first argument with the function named in
+        // their second.
+        let mut fe = me.func(Arity(2), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.push(Operand::Local(LocalRef(1)));
+        fe.call_func(f0);
+        let mut me = fe.finish_and_export("any");
+        let mut fe = me.func(Arity(2), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.push(Operand::Local(LocalRef(1)));
+        fe.call_func(f1);
+        fe.finish_and_export("all").finish()
+    }
+
+    #[cfg(feature = "next")]
+    pub(crate) fn wasm_module_with_map_filter_values() -> Vec<u8> {
+        let mut me = ModEmitter::default_with_test_protocol();
+        // map_filter_values
+        let f0 = me.import_func("m", "i", Arity(2));
+        // a predicate: whether a `U32Val` argument is greater than 8, as the
+        // top bit of its upper 32 bits plus `2^63 - 9`, returned as `Bool`
+        // (payload 0 or 1).
+        let mut fe = me.func(Arity(1), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.i64_const(32);
+        fe.i64_shr_u();
+        fe.i64_const(i64::MAX - 8);
+        fe.i64_add();
+        fe.i64_const(63);
+        fe.i64_shr_u();
+        let mut me = fe.finish_and_export("greater_than_8");
+        // the caller: filters its first argument by the function named in its
+        // second.
+        let mut fe = me.func(Arity(2), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.push(Operand::Local(LocalRef(1)));
+        fe.call_func(f0);
+        fe.finish_and_export("filter").finish()
+    }
+
+    #[cfg(feature = "next")]
+    pub(crate) fn wasm_module_with_map_fold() -> Vec<u8> {
+        let mut me = ModEmitter::default_with_test_protocol();
+        // map_fold
+        let f0 = me.import_func("m", "d", Arity(3));
+        // a folding function: adds a `U32Val` value to a `U32Val` accumulator
+        // by adding the value's upper 32 bits, leaving the accumulator's tag
+        // in place. The key is ignored.
+        let mut fe = me.func(Arity(3), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.push(Operand::Local(LocalRef(2)));
+        fe.i64_const(32);
+        fe.i64_shr_u();
+        fe.i64_const(32);
+        fe.i64_shl();
+        fe.i64_add();
+        let mut me = fe.finish_and_export("add_val");
+        // the caller: folds its first argument from its second, with the
+        // function named in its third.
+        let mut fe = me.func(Arity(3), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.push(Operand::Local(LocalRef(1)));
+        fe.push(Operand::Local(LocalRef(2)));
+        fe.call_func(f0);
+        fe.finish_and_export("fold").finish()
+    }
+
+    #[cfg(feature = "next")]
+    pub(crate) fn wasm_module_with_map_merge_with() -> Vec<u8> {
+        let mut me = ModEmitter::default_with_test_protocol();
+        // map_merge_with
+        let f0 = me.import_func("m", "l", Arity(3));
+        // a combiner: adds two `U32Val`s by adding the second's upper 32 bits
+        // to the first, leaving the first's tag in place.
+        let mut fe = me.func(Arity(2), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.push(Operand::Local(LocalRef(1)));
+        fe.i64_const(32);
+        fe.i64_shr_u();
+        fe.i64_const(32);
+        fe.i64_shl();
+        fe.i64_add();
+        let mut me = fe.finish_and_export("add_val");
+        // the caller: merges its first two arguments with the function named
+        // in its third.
+        let mut fe = me.func(Arity(3), 0);
+        fe.push(Operand::Local(LocalRef(0)));
+        fe.push(Operand::Local(LocalRef(1)));
+        fe.push(Operand::Local(LocalRef(2)));
+        fe.call_func(f0);
+        fe.finish_and_export("merge").finish()
+    }
+
+    #[cfg(feature = "next")]
+    pub(crate) fn wasm_module_returning_code_hash() -> Vec<u8> {
+        let mut me = ModEmitter::default_with_test_protocol();
+        // get_current_contract_code_hash
+        let f0 = me.import_func("x", "c", Arity(0));
+        let mut fe = me.func(Arity(0), 0);
+        fe.call_func(f0);
+        fe.finish_and_export("code_hash").finish()
+    }
 }
 
 #[allow(clippy::type_complexity)]
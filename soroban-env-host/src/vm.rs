@@ -107,7 +107,7 @@ impl Host {
     ) -> Result<wasmi::Linker<Host>, HostError> {
         let mut linker = wasmi::Linker::new(&engine);
         for hf in HOST_FUNCTIONS {
-            if symbols.contains(&(hf.mod_str, hf.fn_str)) {
+            if hf.is_linked() && symbols.contains(&(hf.mod_str, hf.fn_str)) {
                 context.map_err((hf.wrap)(&mut linker).map_err(|le| wasmi::Error::Linker(le)))?;
             }
         }
@@ -120,7 +120,7 @@ impl Host {
         engine: &wasmi::Engine,
     ) -> Result<wasmi::Linker<Host>, HostError> {
         let mut linker = wasmi::Linker::new(&engine);
-        for hf in HOST_FUNCTIONS {
+        for hf in HOST_FUNCTIONS.iter().filter(|hf| hf.is_linked()) {
             context.map_err((hf.wrap)(&mut linker).map_err(|le| wasmi::Error::Linker(le)))?;
         }
         Ok(linker)
@@ -322,26 +322,27 @@ impl Vm {
 
         // call the function
         let mut wasm_ret: [wasmi::Value; 1] = [wasmi::Value::I64(0)];
-        self.wasmi_store
-            .try_borrow_mut_or_err()?
-            .add_fuel_to_vm(host)?;
-        // Metering: the `func.call` will trigger `wasmi::Call` (or `CallIndirect`) instruction,
-        // which is technically covered by wasmi fuel metering. So we are double charging a bit
-        // here (by a few 100s cpu insns). It is better to be safe.
-        let res = func.call(
-            &mut *self.wasmi_store.try_borrow_mut_or_err()?,
-            inputs,
-            &mut wasm_ret,
-        );
-        // Due to the way wasmi's fuel metering works (it does `remaining.checked_sub(delta).ok_or(Trap)`),
-        // there may be a small amount of fuel (less than delta -- the fuel cost of that failing
-        // wasmi instruction) remaining when the `OutOfFuel` trap occurs. This is only observable
-        // if the contract traps with `OutOfFuel`, which may appear confusing if they look closely
-        // at the budget amount consumed. So it should be fine.
-        self.wasmi_store
-            .try_borrow_mut_or_err()?
-            .return_fuel_to_host(host)?;
+        // This call's instructions are counted separately from its caller's,
+        // for the limit set by `Host::set_max_insns_per_call`. Without a limit
+        // nothing is counted.
+        let count_insns = host.try_borrow_max_insns_per_call()?.is_some();
+        if count_insns {
+            host.try_borrow_vm_call_insns_mut()?.push(0);
+        }
+        let fueled_res = self.fueled_func_call(host, &func, inputs, &mut wasm_ret);
+        if count_insns {
+            host.try_borrow_vm_call_insns_mut()?.pop();
+        }
+        let (res, hit_insn_limit) = fueled_res?;
 
+        if hit_insn_limit {
+            return Err(host.err(
+                ScErrorType::Budget,
+                ScErrorCode::ExceededLimit,
+                "contract call exceeded the per-call Wasm instruction limit",
+                &[func_sym.to_val()],
+            ));
+        }
         if let Err(e) = res {
             use std::borrow::Cow;
 
@@ -388,6 +389,46 @@ impl Vm {
         )
     }
 
+    // Supplies the VM with fuel, calls `func` and returns the unspent fuel to
+    // the host. Along with the call's result, returns whether the call ran
+    // out of fuel because of the per-call instruction limit.
+    fn fueled_func_call(
+        &self,
+        host: &Host,
+        func: &wasmi::Func,
+        inputs: &[wasmi::Value],
+        wasm_ret: &mut [wasmi::Value],
+    ) -> Result<(Result<(), wasmi::Error>, bool), HostError> {
+        self.wasmi_store
+            .try_borrow_mut_or_err()?
+            .add_fuel_to_vm(host)?;
+        // Metering: the `func.call` will trigger `wasmi::Call` (or `CallIndirect`) instruction,
+        // which is technically covered by wasmi fuel metering. So we are double charging a bit
+        // here (by a few 100s cpu insns). It is better to be safe.
+        let res = func.call(
+            &mut *self.wasmi_store.try_borrow_mut_or_err()?,
+            inputs,
+            wasm_ret,
+        );
+        // Due to the way wasmi's fuel metering works (it does `remaining.checked_sub(delta).ok_or(Trap)`),
+        // there may be a small amount of fuel (less than delta -- the fuel cost of that failing
+        // wasmi instruction) remaining when the `OutOfFuel` trap occurs. This is only observable
+        // if the contract traps with `OutOfFuel`, which may appear confusing if they look closely
+        // at the budget amount consumed. So it should be fine.
+        self.wasmi_store
+            .try_borrow_mut_or_err()?
+            .return_fuel_to_host(host)?;
+        let out_of_fuel = match &res {
+            Err(wasmi::Error::Trap(trap)) => {
+                matches!(trap.trap_code(), Some(wasmi::core::TrapCode::OutOfFuel))
+            }
+            Err(wasmi::Error::Store(wasmi::errors::FuelError::OutOfFuel)) => true,
+            _ => false,
+        };
+        let hit_insn_limit = out_of_fuel && host.vm_call_insn_limit_reached()?;
+        Ok((res, hit_insn_limit))
+    }
+
     pub(crate) fn invoke_function_raw(
         self: &Rc<Self>,
         host: &Host,
@@ -5,7 +5,7 @@ use crate::{
 };
 use crate::{
     AddressObject, Bool, BytesObject, DurationObject, Error, ErrorHandler, I128Object, I256Object,
-    I256Val, I64Object, MapObject, StorageType, StringObject, Symbol, SymbolObject,
+    I256Val, I32Val, I64Object, I64Val, MapObject, StorageType, StringObject, Symbol, SymbolObject,
     TimepointObject, U128Object, U256Object, U256Val, U32Val, U64Object, U64Val, Val, VecObject,
     Void,
 };
@@ -115,6 +115,7 @@ impl_relative_object_conversion!(U128Object);
 impl_relative_object_conversion!(U256Object);
 
 impl_relative_object_conversion!(U64Val);
+impl_relative_object_conversion!(I64Val);
 impl_relative_object_conversion!(U256Val);
 impl_relative_object_conversion!(I256Val);
 
@@ -126,6 +127,7 @@ impl RelativeObjectConversion for Bool {}
 impl RelativeObjectConversion for Error {}
 impl RelativeObjectConversion for StorageType {}
 impl RelativeObjectConversion for U32Val {}
+impl RelativeObjectConversion for I32Val {}
 
 ///////////////////////////////////////////////////////////////////////////////
 /// X-macro use: dispatch functions
@@ -28,12 +28,13 @@ pub(crate) trait FuelRefillable {
                 &[],
             ));
         }
-        let fuel = host.as_budget().get_wasmi_fuel_remaining()?;
+        let fuel = host.cap_vm_call_fuel(host.as_budget().get_wasmi_fuel_remaining()?)?;
         self.add_fuel(fuel)
     }
 
     fn return_fuel_to_host(&mut self, host: &Host) -> Result<(), HostError> {
         let fuel = self.fuel_consumed()?;
+        host.count_vm_call_insns(fuel)?;
         host.as_budget()
             .bulk_charge(ContractCostType::WasmInsnExec, fuel, None)?;
         self.reset_fuel()
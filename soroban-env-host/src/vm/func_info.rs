@@ -27,6 +27,16 @@ pub(crate) struct HostFuncInfo {
     pub(crate) max_proto: Option<u32>,
 }
 
+impl HostFuncInfo {
+    /// Whether this host function is linked into contracts in this build.
+    /// `get_wall_time_nanos` is left out of builds that can't enable
+    /// benchmark mode, so production contracts can't import it at all.
+    pub(crate) fn is_linked(&self) -> bool {
+        cfg!(any(test, feature = "testutils", feature = "bench"))
+            || (self.mod_str, self.fn_str) != ("x", "m")
+    }
+}
+
 macro_rules! fn_arity {
     (($($args:ident : $tys:ident),*)) => {
         fn_arity!(@count_args 0, $($args:$tys)*)
@@ -204,6 +204,24 @@ impl ParsedModule {
         callback(&symbols)
     }
 
+    /// Returns whether the module exports a function named `name`.
+    pub fn has_func_export(&self, name: &str) -> bool {
+        self.wasmi_module
+            .exports()
+            .any(|e| e.name() == name && e.ty().func().is_some())
+    }
+
+    /// Returns the number of parameters of the function exported as `name`,
+    /// or `None` if the module exports no function by that name. Every
+    /// parameter of a contract function is a `Val`, so this is the number of
+    /// arguments it takes.
+    pub fn func_export_arity(&self, name: &str) -> Option<u32> {
+        self.wasmi_module
+            .exports()
+            .find(|e| e.name() == name)
+            .and_then(|e| e.ty().func().map(|f| f.params().len() as u32))
+    }
+
     pub fn make_wasmi_linker(&self, host: &Host) -> Result<wasmi::Linker<Host>, HostError> {
         self.with_import_symbols(host, |symbols| {
             Host::make_minimal_wasmi_linker_for_symbols(host, self.wasmi_module.engine(), symbols)
@@ -37,6 +37,7 @@ const SUBTYPES: &[(&str, &str)] = &[
     ("Val", "AddressObject"),
     ("Val", "Symbol"),
     ("Val", "U64Val"),
+    ("Val", "I64Val"),
     ("Val", "U128Val"),
     ("Val", "I128Val"),
     ("Val", "U256Val"),
@@ -45,6 +46,7 @@ const SUBTYPES: &[(&str, &str)] = &[
     ("Val", "TimepointVal"),
     ("Symbol", "SymbolSmall"),
     ("U64Val", "U64Small"),
+    ("I64Val", "I64Small"),
     ("U128Val", "U128Small"),
     ("I128Val", "I128Small"),
     ("U256Val", "U256Small"),
@@ -53,6 +55,7 @@ const SUBTYPES: &[(&str, &str)] = &[
     ("TimepointVal", "TimepointSmall"),
     ("Symbol", "SymbolObject"),
     ("U64Val", "U64Object"),
+    ("I64Val", "I64Object"),
     ("U128Val", "U128Object"),
     ("I128Val", "I128Object"),
     ("U256Val", "U256Object"),
@@ -80,7 +83,7 @@ fn dfs(edges: &BTreeMap<String, BTreeSet<String>>, ty: &String, set: &mut BTreeS
     }
 }
 
-fn check_function_protocol_is_in_range(func: &Function) -> bool {
+pub(crate) fn check_function_protocol_is_in_range(func: &Function) -> bool {
     let min_supported_proto_is_too_new = func
         .min_supported_protocol
         .is_some_and(|v| v > LEDGER_PROTOCOL_VERSION);
@@ -97,7 +97,10 @@ pub fn generate_wasm_module_with_preloaded_linear_memory(
                 .into_iter()
                 .map(move |f| (m.export.clone(), f))
         })
-        .filter(|(_, f)| f.name.contains("_linear_memory"))
+        .filter(|(_, f)| {
+            f.name.contains("_linear_memory")
+                && crate::synth_dispatch_host_fn_tests::check_function_protocol_is_in_range(f)
+        })
         .map(|(mod_export, hf)| {
             let fn_export = hf.export;
             let arity = hf.args.len() as u32;